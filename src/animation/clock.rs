@@ -0,0 +1,57 @@
+use std::cell::Cell;
+use std::rc::Rc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+pub trait Clock {
+    fn now(&self) -> Duration;
+
+    fn sleep(&self, duration: Duration);
+}
+
+pub struct SystemClock {
+    origin: Instant,
+}
+
+impl SystemClock {
+    pub fn new() -> SystemClock {
+        SystemClock { origin: Instant::now() }
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        self.origin.elapsed()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        thread::sleep(duration)
+    }
+}
+
+// Deterministic clock that only moves when slept on or advanced explicitly.
+// Clones share the same time, so a sink can simulate its own latency.
+#[derive(Clone, Default)]
+pub struct ManualClock {
+    now: Rc<Cell<Duration>>,
+}
+
+impl ManualClock {
+    pub fn new() -> ManualClock {
+        Default::default()
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        self.now.set(self.now.get() + duration);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Duration {
+        self.now.get()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        self.advance(duration)
+    }
+}
@@ -0,0 +1,180 @@
+use super::effect::Effect;
+use devices::{Color, Frame, Keymap, Zone};
+use errors::{ErrorKind, Result};
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlendMode {
+    Normal,
+    Add,
+    Multiply,
+    Screen,
+}
+
+impl BlendMode {
+    fn blend_channel(self, below: f64, above: f64) -> f64 {
+        match self {
+            BlendMode::Normal => above,
+            BlendMode::Add => (below + above).min(1.0),
+            BlendMode::Multiply => below * above,
+            BlendMode::Screen => 1.0 - (1.0 - below) * (1.0 - above),
+        }
+    }
+
+    // Blends `above` onto `below`, `alpha` is the combined opacity and mask weight.
+    pub fn blend(self, below: Color, above: Color, alpha: f64) -> Color {
+        let channel = |b: u8, a: u8| {
+            let (b, a) = (f64::from(b) / 255.0, f64::from(a) / 255.0);
+            let value = b + (self.blend_channel(b, a) - b) * alpha;
+
+            (value * 255.0).round().clamp(0.0, 255.0) as u8
+        };
+
+        Color::new(
+            channel(below.red, above.red),
+            channel(below.green, above.green),
+            channel(below.blue, above.blue),
+        )
+    }
+}
+
+impl FromStr for BlendMode {
+    type Err = ::errors::Error;
+
+    fn from_str(s: &str) -> Result<BlendMode> {
+        match s {
+            "normal" => Ok(BlendMode::Normal),
+            "add" => Ok(BlendMode::Add),
+            "multiply" => Ok(BlendMode::Multiply),
+            "screen" => Ok(BlendMode::Screen),
+            _ => Err(ErrorKind::InvalidBlendMode(s.to_string()).into()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LayerId(u64);
+
+struct Layer {
+    id: LayerId,
+    effect: Box<Effect>,
+    blend: BlendMode,
+    opacity: f64,
+    mask: Option<Zone>,
+    // Per cell weight of the mask, resolved on first render.
+    weights: Option<Vec<f64>>,
+    frame: Option<Frame>,
+}
+
+impl Layer {
+    fn weights(&mut self, frame: &Frame, keymap: Option<&Keymap>) -> &[f64] {
+        let geometry = frame.geometry();
+
+        if self.weights.as_ref().map(|w| w.len() != geometry.len()).unwrap_or(true) {
+            self.weights = Some(match self.mask {
+                Some(ref zone) => {
+                    let mut weights = vec![0.0; geometry.len()];
+                    for (row, col) in zone.cells(geometry, keymap) {
+                        weights[row * geometry.cols + col] = 1.0;
+                    }
+                    weights
+                }
+                None => vec![1.0; geometry.len()],
+            });
+        }
+        self.weights.as_ref().unwrap()
+    }
+}
+
+struct CompositorState {
+    layers: Vec<Layer>,
+    next_id: u64,
+}
+
+// Effect stacking other effects as layers, bottom first. Clones share their
+// layers, so layers can be added and removed while the compositor is running
+// in an animation.
+#[derive(Clone)]
+pub struct Compositor {
+    keymap: Option<&'static Keymap>,
+    state: Rc<RefCell<CompositorState>>,
+}
+
+impl Compositor {
+    pub fn new(keymap: Option<&'static Keymap>) -> Compositor {
+        Compositor {
+            keymap,
+            state: Rc::new(RefCell::new(CompositorState {
+                layers: Vec::new(),
+                next_id: 0,
+            })),
+        }
+    }
+
+    pub fn add_layer(&self, effect: Box<Effect>, blend: BlendMode, opacity: f64, mask: Option<Zone>) -> LayerId {
+        let mut state = self.state.borrow_mut();
+        let id = LayerId(state.next_id);
+
+        state.next_id += 1;
+        state.layers.push(Layer {
+            id,
+            effect,
+            blend,
+            opacity: opacity.clamp(0.0, 1.0),
+            mask,
+            weights: None,
+            frame: None,
+        });
+
+        id
+    }
+
+    pub fn remove_layer(&self, id: LayerId) -> bool {
+        let mut state = self.state.borrow_mut();
+        let count = state.layers.len();
+
+        state.layers.retain(|layer| layer.id != id);
+        state.layers.len() != count
+    }
+
+    pub fn set_opacity(&self, id: LayerId, opacity: f64) {
+        if let Some(layer) = self.state.borrow_mut().layers.iter_mut().find(|layer| layer.id == id) {
+            layer.opacity = opacity.clamp(0.0, 1.0);
+        }
+    }
+}
+
+impl Effect for Compositor {
+    fn render(&mut self, t: f64, frame: &mut Frame) {
+        let keymap = self.keymap;
+        let mut state = self.state.borrow_mut();
+
+        frame.fill(Color::default());
+        for layer in state.layers.iter_mut() {
+            if layer.opacity <= 0.0 {
+                continue;
+            }
+            let mut layer_frame = match layer.frame.take() {
+                Some(layer_frame) if layer_frame.geometry() == frame.geometry() => layer_frame,
+                _ => Frame::new(frame.geometry()),
+            };
+            layer.effect.render(t, &mut layer_frame);
+
+            let (cols, opacity, blend) = (frame.cols(), layer.opacity, layer.blend);
+            let weights = layer.weights(frame, keymap);
+            for row in 0..frame.rows() {
+                for col in 0..cols {
+                    let weight = weights[row * cols + col];
+
+                    if weight > 0.0 {
+                        let color = blend.blend(frame.get(row, col), layer_frame.get(row, col), opacity * weight);
+                        frame.set(row, col, color);
+                    }
+                }
+            }
+            layer.frame = Some(layer_frame);
+        }
+    }
+}
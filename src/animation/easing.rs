@@ -0,0 +1,51 @@
+use errors::{ErrorKind, Result};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Easing {
+    #[default]
+    Linear,
+    Step,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+    Sine,
+}
+
+impl Easing {
+    // Maps the progress `p` in [0, 1] of a transition to the eased progress.
+    pub fn apply(self, p: f64) -> f64 {
+        let p = p.clamp(0.0, 1.0);
+
+        match self {
+            Easing::Linear => p,
+            Easing::Step => {
+                if p < 1.0 {
+                    0.0
+                } else {
+                    1.0
+                }
+            }
+            Easing::EaseIn => p * p,
+            Easing::EaseOut => p * (2.0 - p),
+            Easing::EaseInOut => p * p * (3.0 - 2.0 * p),
+            Easing::Sine => 0.5 - 0.5 * (p * ::std::f64::consts::PI).cos(),
+        }
+    }
+}
+
+impl FromStr for Easing {
+    type Err = ::errors::Error;
+
+    fn from_str(s: &str) -> Result<Easing> {
+        match s {
+            "linear" => Ok(Easing::Linear),
+            "step" => Ok(Easing::Step),
+            "ease-in" => Ok(Easing::EaseIn),
+            "ease-out" => Ok(Easing::EaseOut),
+            "ease-in-out" => Ok(Easing::EaseInOut),
+            "sine" => Ok(Easing::Sine),
+            _ => Err(ErrorKind::InvalidEasing(s.to_string()).into()),
+        }
+    }
+}
@@ -0,0 +1,6 @@
+use devices::Frame;
+
+pub trait Effect {
+    // `t` is the time in seconds since the animation was started.
+    fn render(&mut self, t: f64, frame: &mut Frame);
+}
@@ -0,0 +1,239 @@
+use super::clock::Clock;
+use super::effect::Effect;
+use super::sink::FrameSink;
+use devices::Frame;
+use errors::{ErrorKind, Result};
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TrackStats {
+    pub rendered: u64,
+    pub dropped: u64,
+    pub failed: u64,
+    pub last_round_trip: Duration,
+    pub mean_round_trip: Duration,
+    // Time between frames, longer than asked for while the sink is slower.
+    pub interval: Duration,
+}
+
+struct Track {
+    sink: Box<FrameSink>,
+    effect: Box<Effect>,
+    frame: Frame,
+    interval: Duration,
+    next_due: Duration,
+    stats: TrackStats,
+}
+
+impl Track {
+    fn render(&mut self, t: f64, now: Duration, after: Duration) {
+        let round_trip = after - now;
+
+        self.stats.last_round_trip = round_trip;
+        self.stats.mean_round_trip = if self.stats.rendered == 0 {
+            round_trip
+        } else {
+            (self.stats.mean_round_trip * 7 + round_trip) / 8
+        };
+        self.stats.rendered += 1;
+
+        // A sink that takes longer than the frame interval is paced at the
+        // rate it manages on average. Frames whose slot has already passed
+        // while the device was busy anyway are dropped rather than queued, the
+        // next frame is rendered for the first slot not behind us.
+        let interval = self.interval.max(self.stats.mean_round_trip);
+
+        self.stats.interval = interval;
+        self.next_due += interval;
+        if self.next_due < after {
+            let missed = ((after - self.next_due).as_nanos() / interval.as_nanos() + 1) as u32;
+
+            self.stats.dropped += u64::from(missed);
+            self.next_due += interval * missed;
+        }
+        trace!("{}: frame at {:.3}s took {:?}", self.sink.name(), t, round_trip);
+    }
+}
+
+pub struct Animation<C: Clock> {
+    clock: C,
+    start: Duration,
+    tracks: Vec<Track>,
+}
+
+impl<C: Clock> Animation<C> {
+    pub fn new(clock: C) -> Animation<C> {
+        let start = clock.now();
+
+        Animation {
+            clock,
+            start,
+            tracks: Vec::new(),
+        }
+    }
+
+    pub fn clock(&self) -> &C {
+        &self.clock
+    }
+
+    pub fn add_track(&mut self, sink: Box<FrameSink>, effect: Box<Effect>, fps: u32) -> Result<usize> {
+        if fps == 0 {
+            return Err(ErrorKind::InvalidFrameRate(fps).into());
+        }
+        let frame = Frame::new(sink.geometry());
+
+        self.tracks.push(Track {
+            sink,
+            effect,
+            frame,
+            interval: Duration::from_secs(1) / fps,
+            next_due: self.clock.now(),
+            stats: Default::default(),
+        });
+
+        Ok(self.tracks.len() - 1)
+    }
+
+    pub fn stats(&self) -> Vec<(String, TrackStats)> {
+        self.tracks
+            .iter()
+            .map(|track| (track.sink.name().to_string(), track.stats))
+            .collect()
+    }
+
+    pub fn tick(&mut self) {
+        // All tracks due in this tick show the same point in time, so effects
+        // shared between devices stay in sync.
+        let tick = self.clock.now();
+        let t = (tick - self.start).as_secs_f64();
+
+        for track in self.tracks.iter_mut() {
+            if track.next_due > tick {
+                continue;
+            }
+            let now = self.clock.now();
+
+            track.effect.render(t, &mut track.frame);
+            if let Err(error) = track.sink.upload(&track.frame) {
+                warn!("{}: frame upload failed: {}", track.sink.name(), error);
+                track.stats.failed += 1;
+            }
+            let after = self.clock.now();
+
+            track.render(t, now, after);
+        }
+    }
+
+    // Renders every track right away, whether it is due or not.
+    pub fn flush(&mut self) {
+        let now = self.clock.now();
+
+        for track in self.tracks.iter_mut() {
+            track.next_due = now;
+        }
+        self.tick();
+    }
+
+    pub fn run_for(&mut self, duration: Duration) {
+        let until = self.clock.now() + duration;
+
+        self.run_until(|| false, Some(until));
+    }
+
+    pub fn run_until<F>(&mut self, mut stop: F, until: Option<Duration>)
+    where
+        F: FnMut() -> bool,
+    {
+        loop {
+            let now = self.clock.now();
+
+            if stop() || until.map(|until| now >= until).unwrap_or(false) {
+                break;
+            }
+            self.tick();
+
+            let now = self.clock.now();
+            let mut next = self
+                .tracks
+                .iter()
+                .map(|track| track.next_due)
+                .min()
+                .unwrap_or(now + Duration::from_millis(100));
+            if let Some(until) = until {
+                next = next.min(until);
+            }
+            if next > now {
+                self.clock.sleep(next - now);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use animation::{ManualClock, MemorySink};
+    use devices::Geometry;
+
+    struct Blank;
+
+    impl Effect for Blank {
+        fn render(&mut self, _t: f64, _frame: &mut Frame) {}
+    }
+
+    fn millis(frames: &[(Duration, Frame)]) -> Vec<u64> {
+        frames.iter().map(|&(time, _)| time.as_millis() as u64).collect()
+    }
+
+    fn track(animation: &mut Animation<ManualClock>, latency: u64, fps: u32) -> MemorySink {
+        let clock = animation.clock().clone();
+        let sink = MemorySink::new(Geometry::new(1, 1), clock).with_latency(Duration::from_millis(latency));
+
+        animation.add_track(Box::new(sink.clone()), Box::new(Blank), fps).unwrap();
+        sink
+    }
+
+    #[test]
+    fn renders_at_frame_rate() {
+        let mut animation = Animation::new(ManualClock::new());
+        let sink = track(&mut animation, 0, 10);
+
+        animation.run_for(Duration::from_secs(1));
+
+        assert_eq!(millis(&sink.frames()), (0..10).map(|i| i * 100).collect::<Vec<u64>>());
+        assert_eq!(animation.stats()[0].1.dropped, 0);
+    }
+
+    #[test]
+    fn paces_slow_sinks_at_their_round_trip() {
+        let mut animation = Animation::new(ManualClock::new());
+        // 50ms per frame cannot keep up with 30fps.
+        let sink = track(&mut animation, 50, 30);
+
+        animation.run_for(Duration::from_secs(1));
+
+        // Uploads finish 50ms after each frame started.
+        assert_eq!(millis(&sink.frames()), (1..21).map(|i| i * 50).collect::<Vec<u64>>());
+        let stats = animation.stats()[0].1;
+        assert_eq!(stats.dropped, 0);
+        assert_eq!(stats.interval, Duration::from_millis(50));
+        assert_eq!(stats.mean_round_trip, Duration::from_millis(50));
+    }
+
+    #[test]
+    fn drops_frames_delayed_by_other_sinks() {
+        let mut animation = Animation::new(ManualClock::new());
+        let fast = track(&mut animation, 0, 10);
+        // Blocks the fast track for 250ms whenever it renders.
+        let slow = track(&mut animation, 250, 4);
+
+        animation.run_for(Duration::from_secs(1));
+
+        assert_eq!(millis(&fast.frames()), vec![0, 250, 500, 750]);
+        assert_eq!(millis(&slow.frames()), vec![250, 500, 750, 1000]);
+        let stats = animation.stats();
+        // The slots at 200, 400, 600 and 700ms passed while the slow track uploaded.
+        assert_eq!(stats[0].1.dropped, 4);
+        assert_eq!(stats[1].1.dropped, 0);
+    }
+}
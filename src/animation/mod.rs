@@ -0,0 +1,17 @@
+mod clock;
+mod compositor;
+mod easing;
+mod effect;
+mod engine;
+mod shared;
+mod sink;
+mod terminal;
+
+pub use self::clock::*;
+pub use self::compositor::*;
+pub use self::easing::*;
+pub use self::effect::*;
+pub use self::engine::*;
+pub use self::shared::*;
+pub use self::sink::*;
+pub use self::terminal::*;
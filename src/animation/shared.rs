@@ -0,0 +1,61 @@
+use super::effect::Effect;
+use devices::{Frame, Geometry, Sampling};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+struct SharedState {
+    effect: Box<Effect>,
+    frame: Frame,
+    // Time of the frame currently rendered.
+    rendered: Option<f64>,
+}
+
+// An effect rendered once per point in time for several tracks, each of
+// which shows (part of) it in its own way.
+#[derive(Clone)]
+pub struct SharedEffect {
+    state: Rc<RefCell<SharedState>>,
+}
+
+impl SharedEffect {
+    pub fn new(effect: Box<Effect>, geometry: Geometry) -> SharedEffect {
+        SharedEffect {
+            state: Rc::new(RefCell::new(SharedState {
+                effect,
+                frame: Frame::new(geometry),
+                rendered: None,
+            })),
+        }
+    }
+
+    pub fn with_frame<R, F: FnOnce(&Frame) -> R>(&self, t: f64, f: F) -> R {
+        let mut state = self.state.borrow_mut();
+        let state = &mut *state;
+
+        if state.rendered != Some(t) {
+            state.effect.render(t, &mut state.frame);
+            state.rendered = Some(t);
+        }
+        f(&state.frame)
+    }
+}
+
+// Shows a shared effect resampled to the geometry of the track.
+pub struct ResampledEffect {
+    shared: SharedEffect,
+    sampling: Sampling,
+}
+
+impl ResampledEffect {
+    pub fn new(shared: SharedEffect, sampling: Sampling) -> ResampledEffect {
+        ResampledEffect { shared, sampling }
+    }
+}
+
+impl Effect for ResampledEffect {
+    fn render(&mut self, t: f64, frame: &mut Frame) {
+        let geometry = frame.geometry();
+
+        *frame = self.shared.with_frame(t, |source| source.resample(geometry, self.sampling));
+    }
+}
@@ -0,0 +1,131 @@
+use super::clock::{Clock, ManualClock};
+use devices::{Color, Device, Frame, Geometry, Persistence};
+use errors::{ErrorKind, Result};
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+pub trait FrameSink {
+    fn name(&self) -> &str;
+
+    fn geometry(&self) -> Geometry;
+
+    fn upload(&mut self, frame: &Frame) -> Result<()>;
+}
+
+pub struct DeviceSink {
+    device: Box<Device>,
+    geometry: Geometry,
+}
+
+impl DeviceSink {
+    pub fn new(device: Box<Device>) -> Result<DeviceSink> {
+        match device.geometry() {
+            Some(geometry) => Ok(DeviceSink { device, geometry }),
+            None => Err(ErrorKind::NotSupported.into()),
+        }
+    }
+}
+
+impl FrameSink for DeviceSink {
+    fn name(&self) -> &str {
+        self.device.name()
+    }
+
+    fn geometry(&self) -> Geometry {
+        self.geometry
+    }
+
+    fn upload(&mut self, frame: &Frame) -> Result<()> {
+        self.device.set_frame(frame, Persistence::Transient)
+    }
+}
+
+// Plays frames through the static effect of a device, which is available on
+// all devices but can only show the first cell of a frame. The effect is only
+// resent when the color changes.
+pub struct StaticColorSink {
+    device: Box<Device>,
+    last: Option<Color>,
+}
+
+impl StaticColorSink {
+    pub fn new(device: Box<Device>) -> StaticColorSink {
+        StaticColorSink { device, last: None }
+    }
+}
+
+impl FrameSink for StaticColorSink {
+    fn name(&self) -> &str {
+        self.device.name()
+    }
+
+    fn geometry(&self) -> Geometry {
+        Geometry::new(1, 1)
+    }
+
+    fn upload(&mut self, frame: &Frame) -> Result<()> {
+        let color = frame.get(0, 0);
+
+        if self.last != Some(color) {
+            self.device.set_color(color, Persistence::Transient)?;
+            self.last = Some(color);
+        }
+        Ok(())
+    }
+}
+
+struct MemorySinkState {
+    frames: Vec<(Duration, Frame)>,
+}
+
+// Records every uploaded frame together with the time it was uploaded at.
+// Clones share the recorded frames, so a handle can be kept after the sink
+// has been handed to the engine.
+#[derive(Clone)]
+pub struct MemorySink {
+    geometry: Geometry,
+    clock: ManualClock,
+    latency: Duration,
+    state: Rc<RefCell<MemorySinkState>>,
+}
+
+impl MemorySink {
+    pub fn new(geometry: Geometry, clock: ManualClock) -> MemorySink {
+        MemorySink {
+            geometry,
+            clock,
+            latency: Duration::from_millis(0),
+            state: Rc::new(RefCell::new(MemorySinkState { frames: Vec::new() })),
+        }
+    }
+
+    #[cfg(test)]
+    pub fn with_latency(mut self, latency: Duration) -> MemorySink {
+        self.latency = latency;
+        self
+    }
+
+    pub fn frames(&self) -> Vec<(Duration, Frame)> {
+        self.state.borrow().frames.clone()
+    }
+}
+
+impl FrameSink for MemorySink {
+    fn name(&self) -> &str {
+        "memory"
+    }
+
+    fn geometry(&self) -> Geometry {
+        self.geometry
+    }
+
+    fn upload(&mut self, frame: &Frame) -> Result<()> {
+        if frame.geometry() != self.geometry {
+            return Err(ErrorKind::GeometryMismatch.into());
+        }
+        self.clock.advance(self.latency);
+        self.state.borrow_mut().frames.push((self.clock.now(), frame.clone()));
+        Ok(())
+    }
+}
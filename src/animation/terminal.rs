@@ -0,0 +1,140 @@
+use super::sink::FrameSink;
+use devices::{Color, Frame, Geometry, Keymap, Layout};
+use errors::{ErrorKind, Result};
+use libc;
+use std::cell::RefCell;
+use std::io::{self, Write};
+use std::rc::Rc;
+
+// Draws a frame with 24-bit ANSI colors, two characters per LED. With a
+// keymap only cells with a key are drawn, which gives the keyboard its shape;
+// unlit keys are drawn as dim dots so the layout stays visible.
+pub fn render_frame(frame: &Frame, keymap: Option<&Keymap>) -> Vec<String> {
+    let mut lines = Vec::with_capacity(frame.rows());
+
+    for row in 0..frame.rows() {
+        let mut line = String::new();
+
+        for col in 0..frame.cols() {
+            let is_key = keymap
+                .map(|keymap| keymap.keys().iter().any(|key| key.row == row && key.col == col))
+                .unwrap_or(true);
+
+            if !is_key {
+                line.push_str("\x1b[0m  ");
+            } else if frame.get(row, col) == Color::default() {
+                line.push_str("\x1b[0m\x1b[38;2;80;80;80m··");
+            } else {
+                let color = frame.get(row, col);
+                line.push_str(&format!("\x1b[48;2;{};{};{}m  ", color.red, color.green, color.blue));
+            }
+        }
+        line.push_str("\x1b[0m");
+        lines.push(line);
+    }
+
+    lines
+}
+
+struct Panel {
+    name: &'static str,
+    lines: Vec<String>,
+}
+
+struct TerminalState {
+    panels: Vec<Panel>,
+    // Lines drawn last time, the cursor is moved back up by as many to redraw in place.
+    drawn: usize,
+    // Cursor movements would only garble redirected output.
+    is_terminal: bool,
+}
+
+// Shows frames in the terminal instead of (or next to) the hardware. All
+// sinks of a preview share the screen and are drawn below each other.
+#[derive(Clone)]
+pub struct TerminalPreview {
+    state: Rc<RefCell<TerminalState>>,
+}
+
+impl TerminalPreview {
+    pub fn new() -> TerminalPreview {
+        TerminalPreview {
+            state: Rc::new(RefCell::new(TerminalState {
+                panels: Vec::new(),
+                drawn: 0,
+                is_terminal: unsafe { libc::isatty(libc::STDOUT_FILENO) } == 1,
+            })),
+        }
+    }
+
+    pub fn sink(&self, layout: Layout) -> TerminalSink {
+        let mut state = self.state.borrow_mut();
+
+        state.panels.push(Panel {
+            name: layout.name,
+            lines: Vec::new(),
+        });
+        TerminalSink {
+            preview: self.clone(),
+            panel: state.panels.len() - 1,
+            layout,
+        }
+    }
+
+    fn draw(&self) -> Result<()> {
+        let mut state = self.state.borrow_mut();
+        let mut output = String::new();
+        let mut drawn = 0;
+
+        if !state.is_terminal {
+            return Ok(());
+        }
+        if state.drawn > 0 {
+            output.push_str(&format!("\x1b[{}A", state.drawn));
+        }
+        for panel in state.panels.iter() {
+            output.push_str(&format!("{}\x1b[K\n", panel.name));
+            for line in panel.lines.iter() {
+                output.push_str(&format!("{}\x1b[K\n", line));
+            }
+            drawn += panel.lines.len() + 1;
+        }
+        state.drawn = drawn;
+
+        let stdout = io::stdout();
+        let mut stdout = stdout.lock();
+        stdout.write_all(output.as_bytes())?;
+        stdout.flush()?;
+        Ok(())
+    }
+}
+
+impl Default for TerminalPreview {
+    fn default() -> TerminalPreview {
+        TerminalPreview::new()
+    }
+}
+
+pub struct TerminalSink {
+    preview: TerminalPreview,
+    panel: usize,
+    layout: Layout,
+}
+
+impl FrameSink for TerminalSink {
+    fn name(&self) -> &str {
+        self.layout.name
+    }
+
+    fn geometry(&self) -> Geometry {
+        self.layout.geometry
+    }
+
+    fn upload(&mut self, frame: &Frame) -> Result<()> {
+        if frame.geometry() != self.layout.geometry {
+            return Err(ErrorKind::GeometryMismatch.into());
+        }
+        self.preview.state.borrow_mut().panels[self.panel].lines = render_frame(frame, self.layout.keymap);
+        self.preview.draw()
+    }
+}
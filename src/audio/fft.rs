@@ -0,0 +1,88 @@
+use std::f32::consts::PI;
+
+// In-place iterative radix-2 FFT, the length of both slices has to be a power of two.
+pub fn fft(re: &mut [f32], im: &mut [f32]) {
+    let n = re.len();
+    let mut j = 0;
+
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+
+    let mut len = 2;
+    while len <= n {
+        let angle = -2.0 * PI / len as f32;
+        for start in (0..n).step_by(len) {
+            for k in 0..len / 2 {
+                let (sin, cos) = (angle * k as f32).sin_cos();
+                let (a, b) = (start + k, start + k + len / 2);
+                let tre = re[b] * cos - im[b] * sin;
+                let tim = re[b] * sin + im[b] * cos;
+
+                re[b] = re[a] - tre;
+                im[b] = im[a] - tim;
+                re[a] += tre;
+                im[a] += tim;
+            }
+        }
+        len <<= 1;
+    }
+}
+
+// Magnitudes of the first half of the spectrum of a Hann windowed signal,
+// normalized so a full scale sine is close to 1.
+pub fn spectrum(samples: &[f32]) -> Vec<f32> {
+    let n = samples.len();
+    let mut re: Vec<f32> = samples
+        .iter()
+        .enumerate()
+        .map(|(i, sample)| sample * (0.5 - 0.5 * (2.0 * PI * i as f32 / n as f32).cos()))
+        .collect();
+    let mut im = vec![0.0; n];
+
+    fft(&mut re, &mut im);
+
+    re.iter()
+        .zip(im.iter())
+        .take(n / 2)
+        .map(|(re, im)| (re * re + im * im).sqrt() * 4.0 / n as f32)
+        .collect()
+}
+
+// Groups the spectrum into `count` logarithmically spaced bands between 40Hz
+// and 16kHz, each mapped from -60dB..0dB to 0..1.
+pub fn bands(spectrum: &[f32], sample_rate: u32, count: usize) -> Vec<f64> {
+    let bin_width = sample_rate as f64 / (spectrum.len() * 2) as f64;
+    let low = 40.0f64;
+    let high = 16000.0f64.min(sample_rate as f64 / 2.0);
+
+    (0..count)
+        .map(|band| {
+            let from = low * (high / low).powf(band as f64 / count as f64);
+            let to = low * (high / low).powf((band + 1) as f64 / count as f64);
+            let first = ((from / bin_width) as usize).min(spectrum.len() - 1);
+            let last = ((to / bin_width) as usize).clamp(first + 1, spectrum.len());
+            let peak = spectrum[first..last].iter().cloned().fold(0.0f32, f32::max);
+
+            ((20.0 * f64::from(peak).max(1e-9).log10() + 60.0) / 60.0).clamp(0.0, 1.0)
+        })
+        .collect()
+}
+
+pub fn rms(samples: &[f32]) -> f64 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    let sum: f64 = samples.iter().map(|s| f64::from(*s) * f64::from(*s)).sum();
+
+    (sum / samples.len() as f64).sqrt()
+}
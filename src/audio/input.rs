@@ -0,0 +1,129 @@
+use super::pcm::PcmReader;
+use errors::Result;
+use std::collections::VecDeque;
+use std::io::Read;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+// Samples kept around for a stream, enough for a window at common rates.
+const STREAM_BUFFER: usize = 16384;
+
+struct StreamState {
+    samples: VecDeque<f32>,
+    finished: bool,
+}
+
+#[derive(Clone)]
+enum Source {
+    Clip(Rc<Vec<f32>>),
+    Stream(Arc<Mutex<StreamState>>),
+}
+
+// Mono audio for the visualizers. A clip holds a whole recording and the
+// window is picked by the animation time, so it plays deterministically; a
+// stream is filled by a background thread and always shows the latest samples.
+#[derive(Clone)]
+pub struct AudioInput {
+    sample_rate: u32,
+    source: Source,
+}
+
+impl AudioInput {
+    pub fn clip<R: Read>(mut reader: PcmReader<R>) -> Result<AudioInput> {
+        let mut samples = Vec::new();
+
+        while reader.read_samples(&mut samples)? {}
+
+        Ok(AudioInput {
+            sample_rate: reader.format().sample_rate,
+            source: Source::Clip(Rc::new(samples)),
+        })
+    }
+
+    pub fn stream<R: Read + Send + 'static>(mut reader: PcmReader<R>) -> AudioInput {
+        let sample_rate = reader.format().sample_rate;
+        let state = Arc::new(Mutex::new(StreamState {
+            samples: VecDeque::with_capacity(STREAM_BUFFER),
+            finished: false,
+        }));
+        let thread_state = state.clone();
+
+        thread::spawn(move || {
+            let mut chunk = Vec::new();
+
+            loop {
+                chunk.clear();
+                let more = match reader.read_samples(&mut chunk) {
+                    Ok(more) => more,
+                    Err(error) => {
+                        warn!("Reading audio failed: {}", error);
+                        false
+                    }
+                };
+                let mut state = thread_state.lock().unwrap();
+
+                state.samples.extend(chunk.iter());
+                let excess = state.samples.len().saturating_sub(STREAM_BUFFER);
+                state.samples.drain(..excess);
+                if !more {
+                    state.finished = true;
+                    return;
+                }
+            }
+        });
+
+        AudioInput {
+            sample_rate,
+            source: Source::Stream(state),
+        }
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    // Fills `window` with the samples leading up to time `t`, padding with silence.
+    pub fn window(&self, t: f64, window: &mut [f32]) {
+        for sample in window.iter_mut() {
+            *sample = 0.0;
+        }
+        match self.source {
+            Source::Clip(ref samples) => {
+                let end = ((t * f64::from(self.sample_rate)) as usize).min(samples.len());
+                let start = end.saturating_sub(window.len());
+                let offset = window.len() - (end - start);
+
+                window[offset..].copy_from_slice(&samples[start..end]);
+            }
+            Source::Stream(ref state) => {
+                let state = state.lock().unwrap();
+                let count = state.samples.len().min(window.len());
+                let offset = window.len() - count;
+
+                for (target, sample) in window[offset..]
+                    .iter_mut()
+                    .zip(state.samples.iter().skip(state.samples.len() - count))
+                {
+                    *target = *sample;
+                }
+            }
+        }
+    }
+
+    // Length of a clip in seconds, the length of a stream is not known.
+    pub fn length(&self) -> Option<f64> {
+        match self.source {
+            Source::Clip(ref samples) => Some(samples.len() as f64 / f64::from(self.sample_rate)),
+            Source::Stream(_) => None,
+        }
+    }
+
+    // True once a stream has been read up to its end.
+    pub fn is_closed(&self) -> bool {
+        match self.source {
+            Source::Clip(_) => false,
+            Source::Stream(ref state) => state.lock().unwrap().finished,
+        }
+    }
+}
@@ -0,0 +1,9 @@
+mod fft;
+mod input;
+mod pcm;
+mod visualizer;
+
+pub use self::fft::*;
+pub use self::input::*;
+pub use self::pcm::*;
+pub use self::visualizer::*;
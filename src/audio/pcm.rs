@@ -0,0 +1,325 @@
+use errors::{ErrorKind, Result, ResultExt};
+use std::io::{self, Cursor, Read};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SampleEncoding {
+    U8,
+    S16Le,
+    S32Le,
+    F32Le,
+}
+
+impl SampleEncoding {
+    pub fn bytes(self) -> usize {
+        match self {
+            SampleEncoding::U8 => 1,
+            SampleEncoding::S16Le => 2,
+            SampleEncoding::S32Le | SampleEncoding::F32Le => 4,
+        }
+    }
+
+    fn decode(self, bytes: &[u8]) -> f32 {
+        match self {
+            SampleEncoding::U8 => (f32::from(bytes[0]) - 128.0) / 128.0,
+            SampleEncoding::S16Le => f32::from(i16::from_le_bytes([bytes[0], bytes[1]])) / 32768.0,
+            SampleEncoding::S32Le => {
+                i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f32 / 2_147_483_648.0
+            }
+            SampleEncoding::F32Le => f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+        }
+    }
+}
+
+impl FromStr for SampleEncoding {
+    type Err = ::errors::Error;
+
+    fn from_str(s: &str) -> Result<SampleEncoding> {
+        match s {
+            "u8" => Ok(SampleEncoding::U8),
+            "s16le" => Ok(SampleEncoding::S16Le),
+            "s32le" => Ok(SampleEncoding::S32Le),
+            "f32le" => Ok(SampleEncoding::F32Le),
+            _ => Err(ErrorKind::InvalidAudio(format!("unknown sample format '{}'", s)).into()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PcmFormat {
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub encoding: SampleEncoding,
+}
+
+impl PcmFormat {
+    pub fn validate(&self) -> Result<()> {
+        if self.channels == 0 || self.sample_rate == 0 {
+            return Err(ErrorKind::InvalidAudio("channels and sample rate must be positive".to_string()).into());
+        }
+        Ok(())
+    }
+}
+
+// Decodes interleaved PCM into mono samples in [-1, 1] by averaging the channels.
+pub struct PcmReader<R: Read> {
+    reader: R,
+    format: PcmFormat,
+    buffer: Vec<u8>,
+}
+
+impl<R: Read> PcmReader<R> {
+    pub fn new(reader: R, format: PcmFormat) -> PcmReader<R> {
+        PcmReader {
+            reader,
+            format,
+            buffer: vec![0; 4096 * format.encoding.bytes() * format.channels as usize],
+        }
+    }
+
+    pub fn format(&self) -> PcmFormat {
+        self.format
+    }
+
+    // Appends the next chunk of samples, returns false at the end of the stream.
+    pub fn read_samples(&mut self, samples: &mut Vec<f32>) -> Result<bool> {
+        let sample_bytes = self.format.encoding.bytes();
+        let frame_bytes = sample_bytes * self.format.channels as usize;
+        let mut filled = 0;
+
+        // Only whole frames are decoded, keep reading until at least one is complete.
+        while filled < frame_bytes {
+            match self.reader.read(&mut self.buffer[filled..]) {
+                Ok(0) => return Ok(false),
+                Ok(count) => filled += count,
+                Err(ref error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                Err(error) => return Err(error.into()),
+            }
+        }
+        let complete = filled - filled % frame_bytes;
+        if complete < filled {
+            self.reader
+                .read_exact(&mut self.buffer[filled..complete + frame_bytes])?;
+            filled = complete + frame_bytes;
+        }
+
+        for frame in self.buffer[..filled].chunks(frame_bytes) {
+            let sum: f32 = frame
+                .chunks(sample_bytes)
+                .map(|sample| self.format.encoding.decode(sample))
+                .sum();
+            samples.push(sum / f32::from(self.format.channels));
+        }
+
+        Ok(true)
+    }
+}
+
+fn read_u16<R: Read>(reader: &mut R) -> Result<u16> {
+    let mut bytes = [0u8; 2];
+    reader.read_exact(&mut bytes)?;
+    Ok(u16::from_le_bytes(bytes))
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> Result<u32> {
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn invalid_wav(message: &str) -> ::errors::Error {
+    ErrorKind::InvalidAudio(message.to_string()).into()
+}
+
+// Reads the wav header up to the start of the sample data, returns the
+// format and the size of the data chunk.
+fn read_wav_header<R: Read>(reader: &mut R) -> Result<(PcmFormat, u32)> {
+    let mut tag = [0u8; 4];
+
+    read_u32(reader)?;
+    reader.read_exact(&mut tag)?;
+    if &tag != b"WAVE" {
+        return Err(invalid_wav("not a WAVE file"));
+    }
+
+    let mut format = None;
+    loop {
+        reader.read_exact(&mut tag)?;
+        let size = read_u32(reader)?;
+
+        match &tag {
+            b"fmt " => {
+                let audio_format = read_u16(reader)?;
+                let channels = read_u16(reader)?;
+                let sample_rate = read_u32(reader)?;
+                read_u32(reader)?;
+                read_u16(reader)?;
+                let bits = read_u16(reader)?;
+                let rest = u64::from(size)
+                    .checked_sub(16)
+                    .ok_or_else(|| invalid_wav("format chunk too small"))?;
+                io::copy(&mut reader.take(rest), &mut io::sink())?;
+
+                let encoding = match (audio_format, bits) {
+                    (1, 8) => SampleEncoding::U8,
+                    (1, 16) => SampleEncoding::S16Le,
+                    (1, 32) => SampleEncoding::S32Le,
+                    (3, 32) => SampleEncoding::F32Le,
+                    _ => return Err(invalid_wav("unsupported sample format")),
+                };
+                let parsed = PcmFormat {
+                    sample_rate,
+                    channels,
+                    encoding,
+                };
+                parsed.validate().chain_err(|| invalid_wav("invalid format chunk"))?;
+                format = Some(parsed);
+            }
+            b"data" => {
+                return format
+                    .map(|format| (format, size))
+                    .ok_or_else(|| invalid_wav("data before format chunk"))
+            }
+            _ => {
+                // Chunks are padded to an even size.
+                io::copy(&mut reader.take(u64::from(size) + u64::from(size % 2)), &mut io::sink())?;
+            }
+        }
+    }
+}
+
+// Opens a wav stream, or raw PCM in the given format if the stream does not
+// start with a RIFF header.
+pub fn open_pcm<R>(mut reader: R, raw_format: PcmFormat) -> Result<PcmReader<Box<Read + Send>>>
+where
+    R: Read + Send + 'static,
+{
+    let mut magic = [0u8; 4];
+    let mut read = 0;
+
+    while read < magic.len() {
+        match reader.read(&mut magic[read..])? {
+            0 => break,
+            count => read += count,
+        }
+    }
+    if read == magic.len() && &magic == b"RIFF" {
+        let (format, size) = read_wav_header(&mut reader)?;
+
+        // Chunks after the samples (LIST, id3 ...) are not audio.
+        Ok(PcmReader::new(Box::new(reader.take(u64::from(size))), format))
+    } else {
+        raw_format.validate()?;
+        let head = Cursor::new(magic[..read].to_vec());
+
+        Ok(PcmReader::new(Box::new(head.chain(reader)), raw_format))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SINE: &[u8] = include_bytes!("../../fixtures/audio/sine_1khz.wav");
+    const TRAILING_ID3: &[u8] = include_bytes!("../../fixtures/audio/trailing_id3.wav");
+
+    const RAW: PcmFormat = PcmFormat {
+        sample_rate: 8000,
+        channels: 1,
+        encoding: SampleEncoding::U8,
+    };
+
+    // A wav file with one fmt chunk of `fmt_size` bytes, followed by `chunks`.
+    fn wav(fmt_size: u32, channels: u16, sample_rate: u32, chunks: &[u8]) -> Vec<u8> {
+        let mut bytes = b"RIFF\0\0\0\0WAVEfmt ".to_vec();
+
+        bytes.extend_from_slice(&fmt_size.to_le_bytes());
+        bytes.extend_from_slice(&1u16.to_le_bytes());
+        bytes.extend_from_slice(&channels.to_le_bytes());
+        bytes.extend_from_slice(&sample_rate.to_le_bytes());
+        bytes.extend_from_slice(&(sample_rate * 2).to_le_bytes());
+        bytes.extend_from_slice(&2u16.to_le_bytes());
+        bytes.extend_from_slice(&16u16.to_le_bytes());
+        bytes.extend_from_slice(chunks);
+        bytes
+    }
+
+    fn read_all(bytes: Vec<u8>, raw_format: PcmFormat) -> Result<(PcmFormat, Vec<f32>)> {
+        let mut reader = open_pcm(Cursor::new(bytes), raw_format)?;
+        let mut samples = Vec::new();
+
+        while reader.read_samples(&mut samples)? {}
+        Ok((reader.format(), samples))
+    }
+
+    #[test]
+    fn reads_wav_header() {
+        let mut reader = Cursor::new(&SINE[4..]);
+        let (format, size) = read_wav_header(&mut reader).unwrap();
+
+        assert_eq!(
+            format,
+            PcmFormat {
+                sample_rate: 16000,
+                channels: 1,
+                encoding: SampleEncoding::S16Le,
+            }
+        );
+        // The padded LIST chunk is skipped up to the samples.
+        assert_eq!(reader.position() as usize, SINE.len() - 4 - 4096);
+        assert_eq!(size, 4096);
+    }
+
+    #[test]
+    fn reads_samples() {
+        let (_, samples) = read_all(SINE.to_vec(), RAW).unwrap();
+        let peak = samples.iter().cloned().fold(0.0f32, f32::max);
+
+        assert_eq!(samples.len(), 2048);
+        assert_eq!(samples[0], 0.0);
+        assert!((peak - 0.5).abs() < 0.01);
+        // 16 samples per period of the 1kHz sine.
+        assert!((samples[4] - peak).abs() < 0.01);
+    }
+
+    #[test]
+    fn stops_at_the_end_of_the_data_chunk() {
+        let (format, samples) = read_all(TRAILING_ID3.to_vec(), RAW).unwrap();
+
+        assert_eq!(format.sample_rate, 8000);
+        // The odd sized id3 chunk after the samples is not decoded.
+        assert_eq!(samples.len(), 64);
+        assert_eq!(samples[63], 63.0 * 512.0 / 32768.0);
+    }
+
+    #[test]
+    fn averages_channels() {
+        let bytes = vec![255, 1, 192, 192];
+        let (_, samples) = read_all(bytes, PcmFormat { channels: 2, ..RAW }).unwrap();
+
+        assert_eq!(samples, vec![0.0, 0.5]);
+    }
+
+    #[test]
+    fn rejects_small_format_chunk() {
+        let bytes = wav(12, 1, 8000, b"data\0\0\0\0");
+
+        assert!(read_all(bytes, RAW).is_err());
+    }
+
+    #[test]
+    fn rejects_oversized_chunk() {
+        let mut chunks = b"junk".to_vec();
+        chunks.extend_from_slice(&u32::MAX.to_le_bytes());
+
+        assert!(read_all(wav(16, 1, 8000, &chunks), RAW).is_err());
+    }
+
+    #[test]
+    fn rejects_empty_formats() {
+        assert!(read_all(wav(16, 0, 8000, b"data\0\0\0\0"), RAW).is_err());
+        assert!(read_all(wav(16, 1, 0, b"data\0\0\0\0"), RAW).is_err());
+        assert!(read_all(vec![0; 8], PcmFormat { channels: 0, ..RAW }).is_err());
+        assert!(read_all(vec![0; 8], PcmFormat { sample_rate: 0, ..RAW }).is_err());
+    }
+}
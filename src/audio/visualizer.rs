@@ -0,0 +1,150 @@
+use super::fft;
+use super::input::AudioInput;
+use animation::Effect;
+use devices::{Color, Frame};
+
+const WINDOW: usize = 1024;
+
+// Draws the spectrum as bars rising from the bottom row, one band per column.
+// Single row devices, like the strips of a mouse, show a level meter instead.
+pub struct SpectrumVisualizer {
+    input: AudioInput,
+    low: Color,
+    high: Color,
+    gain: f64,
+    // Falloff of the bars in full height per second.
+    decay: f64,
+    levels: Vec<f64>,
+    window: Vec<f32>,
+    last: f64,
+}
+
+impl SpectrumVisualizer {
+    pub fn new(input: AudioInput, low: Color, high: Color, gain: f64) -> SpectrumVisualizer {
+        SpectrumVisualizer {
+            input,
+            low,
+            high,
+            gain,
+            decay: 2.0,
+            levels: Vec::new(),
+            window: vec![0.0; WINDOW],
+            last: 0.0,
+        }
+    }
+
+    fn update(&mut self, t: f64, values: Vec<f64>) {
+        let falloff = (t - self.last).max(0.0) * self.decay;
+
+        if self.levels.len() != values.len() {
+            self.levels = vec![0.0; values.len()];
+        }
+        for (level, value) in self.levels.iter_mut().zip(values) {
+            *level = (value * self.gain).min(1.0).max(*level - falloff);
+        }
+        self.last = t;
+    }
+}
+
+impl Effect for SpectrumVisualizer {
+    fn render(&mut self, t: f64, frame: &mut Frame) {
+        let (rows, cols) = (frame.rows(), frame.cols());
+
+        self.input.window(t, &mut self.window);
+        if rows == 1 {
+            // Map -48dB..0dB of the RMS level onto the strip.
+            let rms = fft::rms(&self.window).max(1e-9);
+            let level = ((20.0 * rms.log10() + 48.0) / 48.0).clamp(0.0, 1.0);
+            self.update(t, vec![level]);
+        } else {
+            let spectrum = fft::spectrum(&self.window);
+            let bands = fft::bands(&spectrum, self.input.sample_rate(), cols);
+            self.update(t, bands);
+        }
+
+        frame.fill(Color::default());
+        if rows == 1 {
+            let lit = self.levels[0] * cols as f64;
+
+            for col in 0..cols {
+                let fill = (lit - col as f64).clamp(0.0, 1.0);
+                let color = self.low.mix(self.high, col as f64 / cols as f64);
+                frame.set(0, col, color.scale(fill));
+            }
+        } else {
+            for col in 0..cols {
+                let height = self.levels[col] * rows as f64;
+
+                for level in 0..rows {
+                    let fill = (height - level as f64).clamp(0.0, 1.0);
+                    let color = self.low.mix(self.high, level as f64 / (rows - 1) as f64);
+                    frame.set(rows - 1 - level, col, color.scale(fill));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use audio::{open_pcm, PcmFormat, SampleEncoding};
+    use devices::Geometry;
+    use std::io::Cursor;
+
+    const SINE: &[u8] = include_bytes!("../../fixtures/audio/sine_1khz.wav");
+
+    fn sine() -> AudioInput {
+        let raw = PcmFormat {
+            sample_rate: 8000,
+            channels: 1,
+            encoding: SampleEncoding::U8,
+        };
+
+        AudioInput::clip(open_pcm(Cursor::new(SINE), raw).unwrap()).unwrap()
+    }
+
+    fn lit(frame: &Frame, col: usize) -> usize {
+        (0..frame.rows()).filter(|&row| frame.get(row, col) != Color::default()).count()
+    }
+
+    #[test]
+    fn draws_bar_for_the_tone() {
+        let white = Color::new(255, 255, 255);
+        let mut visualizer = SpectrumVisualizer::new(sine(), white, white, 1.0);
+        let mut frame = Frame::new(Geometry::new(6, 22));
+
+        visualizer.render(0.128, &mut frame);
+
+        // 1kHz falls into band 13 of 22 between 40Hz and 8kHz, at about -6dB.
+        let heights: Vec<usize> = (0..22).map(|col| lit(&frame, col)).collect();
+        let tallest = (0..22).max_by_key(|&col| heights[col]).unwrap();
+        assert_eq!(tallest, 13);
+        assert_eq!(heights[13], 6);
+        assert_eq!(heights[0], 0);
+        assert_eq!(heights[21], 0);
+    }
+
+    #[test]
+    fn draws_level_on_strips() {
+        let white = Color::new(255, 255, 255);
+        let mut visualizer = SpectrumVisualizer::new(sine(), white, white, 1.0);
+        let mut frame = Frame::new(Geometry::new(1, 16));
+
+        visualizer.render(0.128, &mut frame);
+
+        // The RMS of a sine at half scale is about -9dB, 13 of 16 LEDs.
+        assert!((0..13).all(|col| lit(&frame, col) == 1));
+        assert!((13..16).all(|col| lit(&frame, col) == 0));
+    }
+
+    #[test]
+    fn is_dark_before_the_clip() {
+        let mut visualizer = SpectrumVisualizer::new(sine(), Color::new(255, 0, 0), Color::new(0, 0, 255), 1.0);
+        let mut frame = Frame::new(Geometry::new(6, 22));
+
+        visualizer.render(0.0, &mut frame);
+
+        assert!((0..22).all(|col| lit(&frame, col) == 0));
+    }
+}
@@ -0,0 +1,151 @@
+use animation::{Animation, Clock, DeviceSink, Effect, ResampledEffect, SharedEffect, SystemClock, TerminalPreview};
+use desk::{DeskCanvas, DeskLayout};
+use devices::{self, Layout, Sampling};
+use effects::{self, EffectParams};
+use errors::{ErrorKind, Result};
+use std::time::Duration;
+
+// How an effect is spread over the devices.
+pub enum Spread {
+    // Each device runs its own instance.
+    Device,
+    // One instance across all devices placed on the desk.
+    Desk,
+    // One instance rendered for a layout, resampled for every device.
+    Mirror(Layout, Sampling),
+}
+
+pub fn animate(
+    name: &str,
+    params: &EffectParams,
+    fps: u32,
+    duration: Option<Duration>,
+    spread: &Spread,
+    preview: &[Layout],
+) -> Result<()> {
+    let effect = effects::find_effect(name)?;
+    // Report bad parameters even when no device is connected.
+    effect.create(params)?;
+
+    let create = |_: &Layout| Ok(Some(effect.create(params)?));
+    match *spread {
+        Spread::Device => run_effect(fps, duration, preview, create),
+        Spread::Desk => run_desk_effect(&DeskLayout::load_default()?, fps, duration, preview, create),
+        Spread::Mirror(ref source, sampling) => run_mirrored_effect(source, sampling, fps, duration, preview, create),
+    }
+}
+
+// Adds a track for every connected device with custom frames and every
+// preview layout. `create` returns None for layouts the effect cannot be
+// shown on, i.e. without keys.
+pub fn add_tracks<C, F>(animation: &mut Animation<C>, fps: u32, preview: &[Layout], mut create: F) -> Result<()>
+where
+    C: Clock,
+    F: FnMut(&Layout) -> Result<Option<Box<Effect>>>,
+{
+    for device in devices::list_devices()? {
+        let layout = match device.layout() {
+            Some(layout) => layout,
+            None => {
+                println!("{} does not support custom frames", device.name());
+                continue;
+            }
+        };
+        match create(&layout)? {
+            Some(effect) => animation.add_track(Box::new(DeviceSink::new(device)?), effect, fps)?,
+            None => {
+                println!("{} has no addressable keys", device.name());
+                continue;
+            }
+        };
+    }
+
+    add_previews(animation, fps, preview, create)
+}
+
+pub fn add_previews<C, F>(animation: &mut Animation<C>, fps: u32, preview: &[Layout], mut create: F) -> Result<()>
+where
+    C: Clock,
+    F: FnMut(&Layout) -> Result<Option<Box<Effect>>>,
+{
+    let terminal = TerminalPreview::new();
+
+    for layout in preview {
+        if let Some(effect) = create(layout)? {
+            animation.add_track(Box::new(terminal.sink(*layout)), effect, fps)?;
+        }
+    }
+
+    Ok(())
+}
+
+pub fn run_effect<F>(fps: u32, duration: Option<Duration>, preview: &[Layout], create: F) -> Result<()>
+where
+    F: FnMut(&Layout) -> Result<Option<Box<Effect>>>,
+{
+    let mut animation = Animation::new(SystemClock::new());
+
+    add_tracks(&mut animation, fps, preview, create)?;
+    run_animation(animation, duration, || false);
+
+    Ok(())
+}
+
+// Like run_effect, but devices placed on the desk show their part of one
+// effect spanning all of them. Others run the effect on their own.
+pub fn run_desk_effect<F>(
+    desk: &DeskLayout,
+    fps: u32,
+    duration: Option<Duration>,
+    preview: &[Layout],
+    mut create: F,
+) -> Result<()>
+where
+    F: FnMut(&Layout) -> Result<Option<Box<Effect>>>,
+{
+    let canvas = match create(&DeskCanvas::layout(desk))? {
+        Some(effect) => DeskCanvas::new(desk, effect),
+        None => return Err(ErrorKind::InvalidDesk("the effect needs keys".to_string()).into()),
+    };
+
+    run_effect(fps, duration, preview, |layout| match desk.placement(layout.name) {
+        Some(placement) => Ok(Some(Box::new(canvas.view(placement)))),
+        None => create(layout),
+    })
+}
+
+// Renders the effect once for `source` and shows it stretched to fit on every device.
+pub fn run_mirrored_effect<F>(
+    source: &Layout,
+    sampling: Sampling,
+    fps: u32,
+    duration: Option<Duration>,
+    preview: &[Layout],
+    mut create: F,
+) -> Result<()>
+where
+    F: FnMut(&Layout) -> Result<Option<Box<Effect>>>,
+{
+    let shared = match create(source)? {
+        Some(effect) => SharedEffect::new(effect, source.geometry),
+        None => return Err(ErrorKind::NotSupported.into()),
+    };
+
+    run_effect(fps, duration, preview, |_| {
+        Ok(Some(Box::new(ResampledEffect::new(shared.clone(), sampling))))
+    })
+}
+
+pub fn run_animation<C, F>(mut animation: Animation<C>, duration: Option<Duration>, stop: F)
+where
+    C: Clock,
+    F: FnMut() -> bool,
+{
+    let until = duration.map(|duration| animation.clock().now() + duration);
+
+    animation.run_until(stop, until);
+
+    for (name, stats) in animation.stats() {
+        println!("{} {:?}", name, stats);
+    }
+}
@@ -0,0 +1,131 @@
+use devices::{self, Calibration, CalibrationProfiles, Color, ColorFilter, Device, Persistence};
+use errors::{ErrorKind, Result};
+use std::fs;
+use std::io::{self, BufRead, Write};
+
+const GAIN_STEP: f64 = 0.02;
+const GAMMA_STEP: f64 = 0.05;
+
+const REFERENCE_COLORS: &[(&str, Color)] = &[
+    ("red", Color { red: 255, green: 0, blue: 0 }),
+    ("green", Color { red: 0, green: 255, blue: 0 }),
+    ("blue", Color { red: 0, green: 0, blue: 255 }),
+    ("yellow", Color { red: 255, green: 255, blue: 0 }),
+    ("cyan", Color { red: 0, green: 255, blue: 255 }),
+    ("magenta", Color { red: 255, green: 0, blue: 255 }),
+    ("orange", Color { red: 255, green: 128, blue: 0 }),
+    ("gray", Color { red: 128, green: 128, blue: 128 }),
+    ("white", Color { red: 255, green: 255, blue: 255 }),
+];
+
+// Walks through white balance, mid gray and a check of reference colors on
+// the first device matching `pattern`, then prints (or saves) the profile.
+pub fn calibrate(pattern: &str, per_unit: bool, save: bool) -> Result<()> {
+    let lowercase = pattern.to_lowercase();
+    let device = devices::list_unfiltered_devices()?
+        .into_iter()
+        .find(|device| device.name().to_lowercase().contains(&lowercase))
+        .ok_or_else(|| ErrorKind::UnknownDevice(pattern.to_string()))?;
+    let serial = if per_unit {
+        Some(device.get_serial()?.to_string_lossy().into_owned())
+    } else {
+        None
+    };
+    let mut profiles = CalibrationProfiles::load_default()?;
+    let mut calibration = profiles.find(device.name(), serial.as_deref()).unwrap_or_default();
+    let stdin = io::stdin();
+    let mut input = stdin.lock();
+
+    println!("Calibrating {}", device.name());
+    println!("Adjust with r, g or b followed by + or - (repeat for bigger steps, e.g. 'b--'), enter to continue.");
+
+    println!("1. Change the channels until white looks neutral, neither tinted nor dim.");
+    while let Some(adjustments) = prompt(&mut input, &*device, &calibration, Color::new(255, 255, 255))? {
+        for (channel, steps) in adjustments {
+            for value in calibration.matrix[channel].iter_mut() {
+                *value *= 1.0 + GAIN_STEP * steps as f64;
+            }
+        }
+    }
+
+    println!("2. Change the channels until gray looks neutral and half as bright as white.");
+    while let Some(adjustments) = prompt(&mut input, &*device, &calibration, Color::new(128, 128, 128))? {
+        for (channel, steps) in adjustments {
+            // A lower gamma brightens the mid tones.
+            calibration.gamma[channel] = (calibration.gamma[channel] - GAMMA_STEP * steps as f64).max(GAMMA_STEP);
+        }
+    }
+
+    println!("3. Check the reference colors, enter shows the next one.");
+    for &(name, color) in REFERENCE_COLORS {
+        print!("{} ", name);
+        io::stdout().flush()?;
+        device.set_color(calibration.apply(color), Persistence::Transient)?;
+        if input.read_line(&mut String::new())? == 0 {
+            break;
+        }
+    }
+
+    match CalibrationProfiles::default_path() {
+        Some(ref path) if save => {
+            if let Some(directory) = path.parent() {
+                fs::create_dir_all(directory)?;
+            }
+            profiles.insert(device.name(), serial.as_deref(), calibration);
+            fs::write(path, profiles.to_toml())?;
+            println!("Saved to {}", path.display());
+        }
+        _ => println!("\n{}", calibration.to_toml(device.name(), serial.as_deref())),
+    }
+
+    Ok(())
+}
+
+// Shows `color` and reads a line of adjustments, None once the user is done.
+fn prompt<R: BufRead>(
+    input: &mut R,
+    device: &Device,
+    calibration: &Calibration,
+    color: Color,
+) -> Result<Option<Vec<(usize, i32)>>> {
+    device.set_color(calibration.apply(color), Persistence::Transient)?;
+
+    loop {
+        print!("> ");
+        io::stdout().flush()?;
+
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 || line.trim().is_empty() {
+            return Ok(None);
+        }
+        match parse_adjustments(&line) {
+            Some(adjustments) => return Ok(Some(adjustments)),
+            None => println!("expected e.g. 'r+', 'g--' or 'b+ r-'"),
+        }
+    }
+}
+
+fn parse_adjustments(line: &str) -> Option<Vec<(usize, i32)>> {
+    line.split_whitespace()
+        .map(|token| {
+            let channel = match token.chars().next()? {
+                'r' => 0,
+                'g' => 1,
+                'b' => 2,
+                _ => return None,
+            };
+            let mut steps = 0;
+
+            for c in token[1..].chars() {
+                match c {
+                    '+' => steps += 1,
+                    '-' => steps -= 1,
+                    _ => return None,
+                }
+            }
+            if steps == 0 && token.len() == 1 {
+                return None;
+            }
+            Some((channel, steps))
+        }).collect()
+}
@@ -0,0 +1,21 @@
+use super::run_effect;
+use animation::Effect;
+use devices::{Color, Layout};
+use errors::Result;
+use std::time::Duration;
+use timer::WallClock;
+
+pub fn clock(
+    hour: Color,
+    minute: Color,
+    background: Color,
+    fps: u32,
+    duration: Option<Duration>,
+    preview: &[Layout],
+) -> Result<()> {
+    run_effect(fps, duration, preview, |layout| {
+        Ok(layout.keymap.map(|keymap| {
+            Box::new(WallClock::new(keymap, hour, minute, background)) as Box<Effect>
+        }))
+    })
+}
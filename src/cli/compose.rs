@@ -0,0 +1,11 @@
+use super::run_effect;
+use devices::Layout;
+use effects::LayerStack;
+use errors::Result;
+use std::time::Duration;
+
+pub fn compose(path: &str, fps: u32, duration: Option<Duration>, preview: &[Layout]) -> Result<()> {
+    let stack = LayerStack::load(path)?;
+
+    run_effect(fps, duration, preview, |layout| Ok(Some(Box::new(stack.build(layout.keymap)?))))
+}
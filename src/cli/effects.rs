@@ -0,0 +1,11 @@
+use effects;
+use errors::Result;
+
+pub fn list_effects() -> Result<()> {
+    for effect in effects::EFFECTS {
+        println!("{:10} {}", effect.name, effect.description);
+        println!("{:10} {}", "", effect.params);
+    }
+
+    Ok(())
+}
@@ -3,7 +3,10 @@ use errors::Result;
 
 pub fn get_brightness() -> Result<()> {
     for device in devices::list_devices()? {
-        println!("{} {:?}", device.name(), device.get_brightness());
+        match device.get_brightness() {
+            Ok(brightness) => println!("{} {}", device.name(), brightness),
+            Err(error) => println!("{} {}", device.name(), error),
+        }
     }
 
     Ok(())
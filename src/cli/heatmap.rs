@@ -0,0 +1,41 @@
+use animation::{Effect, FrameSink, TerminalPreview};
+use devices::{self, Frame, Layout, Persistence};
+use effects::palette::Ramp;
+use errors::Result;
+use keys::{KeyUsage, UsageHeatmap};
+
+pub fn heatmap(
+    path: &str,
+    ramp: &Ramp,
+    logarithmic: bool,
+    persistence: Persistence,
+    preview: &[Layout],
+) -> Result<()> {
+    let usage = KeyUsage::load(path)?;
+
+    for device in devices::list_devices()? {
+        let (geometry, keymap) = match (device.geometry(), device.keymap()) {
+            (Some(geometry), Some(keymap)) => (geometry, keymap),
+            _ => {
+                println!("{} has no addressable keys", device.name());
+                continue;
+            }
+        };
+        let mut frame = Frame::new(geometry);
+
+        UsageHeatmap::new(usage.clone(), keymap, ramp.clone(), logarithmic).render(0.0, &mut frame);
+        println!("{} {:?}", device.name(), device.set_frame(&frame, persistence));
+    }
+
+    let terminal = TerminalPreview::new();
+    for layout in preview {
+        if let Some(keymap) = layout.keymap {
+            let mut frame = Frame::new(layout.geometry);
+
+            UsageHeatmap::new(usage.clone(), keymap, ramp.clone(), logarithmic).render(0.0, &mut frame);
+            terminal.sink(*layout).upload(&frame)?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,37 @@
+use super::{add_tracks, run_animation};
+use animation::{Animation, SystemClock};
+use devices::Layout;
+use effects::{Marquee, MarqueeParams};
+use errors::Result;
+use std::time::Duration;
+
+pub fn marquee(
+    params: &MarqueeParams,
+    once: bool,
+    fps: u32,
+    mut duration: Option<Duration>,
+    preview: &[Layout],
+) -> Result<()> {
+    params.validate()?;
+
+    let mut animation = Animation::new(SystemClock::new());
+    let mut pass_length = 0.0f64;
+
+    add_tracks(&mut animation, fps, preview, |layout| {
+        let effect = Marquee::new(MarqueeParams {
+            text: params.text.clone(),
+            ..*params
+        });
+
+        pass_length = pass_length.max(effect.pass_length(layout.geometry.cols));
+        Ok(Some(Box::new(effect)))
+    })?;
+
+    if once {
+        // Passes too slow for a Duration run until interrupted.
+        duration = Duration::try_from_secs_f64(pass_length).ok();
+    }
+    run_animation(animation, duration, || false);
+
+    Ok(())
+}
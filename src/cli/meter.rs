@@ -0,0 +1,42 @@
+use super::{add_tracks, run_animation};
+use animation::{Animation, SystemClock};
+use devices::{Color, Layout, Zone};
+use errors::{ErrorKind, Result};
+use meter::{Thresholds, ValueInput, ValueMeter};
+use std::io::{self, BufReader};
+use std::time::{Duration, Instant};
+
+pub fn meter(
+    range: (f64, f64),
+    zone: &Zone,
+    thresholds: &Thresholds,
+    empty: Color,
+    fps: u32,
+    preview: &[Layout],
+) -> Result<()> {
+    if range.0.is_nan() || range.1.is_nan() || range.1 <= range.0 {
+        return Err(ErrorKind::InvalidParameter("range".to_string(), format!("{}..{}", range.0, range.1)).into());
+    }
+    let input = ValueInput::read(BufReader::new(io::stdin()));
+    let mut animation = Animation::new(SystemClock::new());
+
+    add_tracks(&mut animation, fps, preview, |layout| {
+        Ok(Some(Box::new(ValueMeter::new(
+            input.clone(),
+            range,
+            zone.clone(),
+            thresholds.clone(),
+            empty,
+            layout.keymap,
+        ))))
+    })?;
+
+    // Keep going for a couple of frames after the end of input, so the last value is shown.
+    let linger = Duration::from_secs_f64(2.0 / fps.max(1) as f64);
+    let mut closed_at = None;
+    run_animation(animation, None, || {
+        input.is_closed() && closed_at.get_or_insert_with(Instant::now).elapsed() >= linger
+    });
+
+    Ok(())
+}
@@ -1,9 +1,43 @@
+mod animate;
+mod calibrate;
+mod clock;
+mod compose;
+mod effects;
 mod get_brightness;
+mod heatmap;
 mod list;
+mod marquee;
+mod meter;
+mod monitor;
+mod play;
+mod react;
+mod render;
+mod script;
 mod set_brightness;
 mod set_color;
+mod set_gradient;
+mod theme;
+mod timer;
+mod visualize;
 
+pub use self::animate::*;
+pub use self::calibrate::*;
+pub use self::clock::*;
+pub use self::compose::*;
+pub use self::effects::*;
 pub use self::get_brightness::*;
+pub use self::heatmap::*;
 pub use self::list::*;
+pub use self::marquee::*;
+pub use self::meter::*;
+pub use self::monitor::*;
+pub use self::play::*;
+pub use self::react::*;
+pub use self::render::*;
+pub use self::script::*;
 pub use self::set_brightness::*;
 pub use self::set_color::*;
+pub use self::set_gradient::*;
+pub use self::theme::*;
+pub use self::timer::*;
+pub use self::visualize::*;
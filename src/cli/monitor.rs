@@ -0,0 +1,35 @@
+use super::run_effect;
+use devices::Layout;
+use effects::palette::Ramp;
+use errors::Result;
+use std::time::Duration;
+use sysmon::{Metric, SystemMonitor, SystemPaths};
+
+pub struct MonitorOptions {
+    pub ramp: Ramp,
+    pub range: (f64, f64),
+    pub interval: f64,
+}
+
+pub fn monitor(
+    metric: Metric,
+    paths: &SystemPaths,
+    options: &MonitorOptions,
+    fps: u32,
+    duration: Option<Duration>,
+    preview: &[Layout],
+) -> Result<()> {
+    let create = || {
+        SystemMonitor::new(
+            metric,
+            paths.clone(),
+            options.ramp.clone(),
+            options.range,
+            options.interval,
+        )
+    };
+    // Fail early if the metric cannot be read at all.
+    create().sample()?;
+
+    run_effect(fps, duration, preview, |_| Ok(Some(Box::new(create()))))
+}
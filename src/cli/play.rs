@@ -0,0 +1,51 @@
+use super::{add_previews, run_animation};
+use animation::{Animation, DeviceSink, FrameSink, StaticColorSink, SystemClock};
+use devices::{self, Layout};
+use errors::Result;
+use std::time::Duration;
+use timeline::{Timeline, TimelineEffect, Track};
+
+pub fn play(path: &str, fps: u32, duration: Option<Duration>, preview: &[Layout]) -> Result<()> {
+    let timeline = Timeline::load(path)?;
+    let mut animation = Animation::new(SystemClock::new());
+
+    for device in devices::list_devices()? {
+        let device_timeline = timeline.for_device(device.name());
+
+        if device_timeline.tracks.is_empty() {
+            continue;
+        }
+        let keymap = device.keymap();
+        let whole_device = device_timeline.tracks.iter().all(|track| track.zone.is_all());
+        // Static colors on the whole device are played through the hardware
+        // static effect, everything else needs custom frames.
+        let sink: Box<FrameSink> = if whole_device
+            && (device.geometry().is_none() || device_timeline.tracks.iter().all(Track::is_static))
+        {
+            Box::new(StaticColorSink::new(device))
+        } else if device.geometry().is_some() {
+            Box::new(DeviceSink::new(device)?)
+        } else {
+            println!("{} does not support custom frames", device.name());
+            continue;
+        };
+
+        animation.add_track(sink, Box::new(TimelineEffect::new(device_timeline, keymap)), fps)?;
+    }
+    add_previews(&mut animation, fps, preview, |layout| {
+        let layout_timeline = timeline.for_device(layout.name);
+
+        if layout_timeline.tracks.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(Box::new(TimelineEffect::new(layout_timeline, layout.keymap))))
+    })?;
+
+    let duration = match timeline.loop_range {
+        Some(_) => duration,
+        None => duration.or_else(|| Duration::try_from_secs_f64(timeline.duration()).ok()),
+    };
+    run_animation(animation, duration, || false);
+
+    Ok(())
+}
@@ -0,0 +1,66 @@
+use super::{add_tracks, run_animation};
+use animation::{Animation, SystemClock};
+use devices::{Color, Layout};
+use effects::palette::Ramp;
+use effects::{Ripple, RippleParams};
+use errors::Result;
+use keys::{Afterglow, EvdevSource, KeySource, Reaction, ReactionKind, Reactive, ReplaySource, TypingHeatmap};
+use std::time::Duration;
+
+pub enum KeyInput<'a> {
+    Device(&'a str),
+    Replay(&'a str),
+}
+
+pub struct ReactOptions {
+    pub color: Color,
+    pub fade: f64,
+    pub ramp: Ramp,
+}
+
+fn create_reaction(kind: ReactionKind, options: &ReactOptions) -> Box<Reaction> {
+    match kind {
+        ReactionKind::Ripple => Box::new(Ripple::new(RippleParams {
+            color: options.color,
+            rate: 0.0,
+            speed: 10.0,
+            width: 1.5,
+            seed: 1,
+        })),
+        ReactionKind::Afterglow => Box::new(Afterglow::new(options.color, options.fade)),
+        ReactionKind::Heatmap => Box::new(TypingHeatmap::new(options.ramp.clone(), options.fade)),
+    }
+}
+
+pub fn react(
+    kind: ReactionKind,
+    input: &KeyInput,
+    options: &ReactOptions,
+    fps: u32,
+    mut duration: Option<Duration>,
+    preview: &[Layout],
+) -> Result<()> {
+    let open = || -> Result<Box<KeySource>> {
+        Ok(match *input {
+            KeyInput::Device(path) => Box::new(EvdevSource::open(path)?),
+            KeyInput::Replay(path) => Box::new(ReplaySource::load(path)?),
+        })
+    };
+
+    // A replay ends once the last press has faded out.
+    if let KeyInput::Replay(path) = *input {
+        let length = ReplaySource::load(path)?.length();
+        duration = duration.or_else(|| Duration::try_from_secs_f64(length + options.fade.max(1.0)).ok());
+    }
+
+    let mut animation = Animation::new(SystemClock::new());
+
+    add_tracks(&mut animation, fps, preview, |layout| match layout.keymap {
+        Some(keymap) => Ok(Some(Box::new(Reactive::new(open()?, keymap, create_reaction(kind, options))))),
+        None => Ok(None),
+    })?;
+
+    run_animation(animation, duration, || false);
+
+    Ok(())
+}
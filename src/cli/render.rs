@@ -0,0 +1,57 @@
+use animation::Effect;
+use devices::{self, Geometry, Layout};
+use effects::{self, EffectParams, ScriptEffect};
+use errors::{ErrorKind, Result};
+use render::{self, ImageStyle};
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+use std::time::Duration;
+
+pub struct RenderOptions {
+    pub fps: u32,
+    pub duration: Duration,
+    pub scale: usize,
+    pub columns: usize,
+}
+
+// Renders a registered effect, or a script when `effect` is a .rhai file, into
+// an animated GIF or a PNG sprite sheet depending on the extension of `output`.
+pub fn render(effect: &str, params: &EffectParams, layout: &Layout, output: &str, options: &RenderOptions) -> Result<()> {
+    let effect: Box<Effect> = if effect.ends_with(".rhai") {
+        Box::new(ScriptEffect::load(effect, layout.keymap)?)
+    } else {
+        effects::find_effect(effect)?.create(params)?
+    };
+    let frames = render::render_frames(effect, layout.geometry, options.fps, options.duration)?;
+    if frames.is_empty() {
+        let duration = options.duration.as_secs_f64().to_string();
+        return Err(ErrorKind::InvalidParameter("duration".to_string(), duration).into());
+    }
+    let style = ImageStyle {
+        scale: options.scale.max(2),
+        keymap: layout.keymap,
+    };
+
+    match Path::new(output).extension().and_then(|extension| extension.to_str()) {
+        Some("gif") => render::write_gif(BufWriter::new(File::create(output)?), &frames, options.fps, &style)?,
+        Some("png") => render::write_sprite_sheet(BufWriter::new(File::create(output)?), &frames, options.columns, &style)?,
+        _ => return Err(ErrorKind::InvalidParameter("output".to_string(), output.to_string()).into()),
+    }
+    println!("{} frames of {}x{} written to {}", frames.len(), layout.geometry.rows, layout.geometry.cols, output);
+
+    Ok(())
+}
+
+// A known device by (part of) its name, or a bare geometry like "6x22".
+pub fn find_layout(device: Option<&str>, geometry: Option<&str>) -> Result<Layout> {
+    match (device, geometry) {
+        (Some(device), _) => Ok(devices::find_layouts(device)?[0]),
+        (None, Some(geometry)) => Ok(Layout {
+            name: "preview",
+            geometry: geometry.parse::<Geometry>()?,
+            keymap: None,
+        }),
+        (None, None) => Err(ErrorKind::InvalidParameter("device".to_string(), String::new()).into()),
+    }
+}
@@ -0,0 +1,14 @@
+use super::run_effect;
+use devices::Layout;
+use effects::ScriptEffect;
+use errors::Result;
+use std::time::Duration;
+
+pub fn run_script(path: &str, fps: u32, duration: Option<Duration>, preview: &[Layout]) -> Result<()> {
+    // Fail early on syntax errors, even when no device is connected.
+    ScriptEffect::load(path, None)?;
+
+    run_effect(fps, duration, preview, |layout| {
+        Ok(Some(Box::new(ScriptEffect::load(path, layout.keymap)?)))
+    })
+}
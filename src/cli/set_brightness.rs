@@ -1,9 +1,57 @@
-use devices;
+use animation::SystemClock;
+use devices::{self, Brightness, LedZone, Persistence};
 use errors::Result;
+use transition::{run_brightness_fades, BrightnessFade, Cancel, Transition};
+
+// Brightness reports are slow on some devices, more steps would queue up.
+const FADE_RATE: u32 = 20;
+
+// Sets all LEDs, or only the `zone` LED on devices that have it.
+pub fn set_brightness(brightness: Brightness, zone: Option<LedZone>, persistence: Persistence) -> Result<()> {
+    for device in devices::list_devices()? {
+        let result = match zone {
+            Some(zone) => device.set_zone_brightness(zone, brightness, persistence),
+            None => device.set_brightness(brightness, persistence),
+        };
+        println!("{} {:?}", device.name(), result);
+    }
+
+    Ok(())
+}
+
+// Fades from `from`, or the current brightness of each device (or of its
+// `zone` LED), to `to`.
+// Ctrl-C stops the fade at the brightness reached so far. Only the final
+// brightness can be stored.
+pub fn fade_brightness(
+    from: Option<Brightness>,
+    to: Brightness,
+    zone: Option<LedZone>,
+    transition: Transition,
+    persistence: Persistence,
+) -> Result<()> {
+    let mut fades = Vec::new();
 
-pub fn set_brightness(brightness: u8) -> Result<()> {
     for device in devices::list_devices()? {
-        println!("{} {:?}", device.name(), device.set_brightness(brightness));
+        let start = match (from, zone) {
+            (Some(from), _) => from,
+            (None, None) => device.get_brightness()?,
+            // Devices without the LED are left out, as when setting it.
+            (None, Some(zone)) => match device.get_zone_brightness(zone) {
+                Ok(brightness) => brightness,
+                Err(error) => {
+                    println!("{} {:?}", device.name(), Err::<(), _>(error));
+                    continue;
+                }
+            },
+        };
+        fades.push((device, BrightnessFade::new(start, to, transition)));
+    }
+
+    if !run_brightness_fades(&SystemClock::new(), &fades, zone, FADE_RATE, &Cancel::on_interrupt())? {
+        println!("cancelled");
+    } else if persistence == Persistence::Stored {
+        set_brightness(to, zone, persistence)?;
     }
 
     Ok(())
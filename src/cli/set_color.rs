@@ -1,9 +1,54 @@
-use devices::{self, Color};
+use super::add_previews;
+use animation::{Animation, Clock, DeviceSink, FrameSink, StaticColorSink, SystemClock};
+use devices::{self, Color, Layout, Persistence};
 use errors::Result;
+use transition::{Cancel, ColorFade, Transition};
 
-pub fn set_color(color: Color) -> Result<()> {
+pub fn set_color(color: Color, persistence: Persistence) -> Result<()> {
     for device in devices::list_devices()? {
-        println!("{} {:?}", device.name(), device.set_color(color));
+        println!("{} {:?}", device.name(), device.set_color(color, persistence));
+    }
+
+    Ok(())
+}
+
+// Fades all devices from `from` to `to`, through custom frames where
+// supported and repeated static effects elsewhere. Ctrl-C stops the fade at
+// the color reached so far. Only the final color can be stored.
+pub fn fade_color(
+    from: Color,
+    to: Color,
+    transition: Transition,
+    fps: u32,
+    persistence: Persistence,
+    preview: &[Layout],
+) -> Result<()> {
+    let cancel = Cancel::on_interrupt();
+    let mut animation = Animation::new(SystemClock::new());
+
+    for device in devices::list_devices()? {
+        let sink: Box<FrameSink> = if device.geometry().is_some() {
+            Box::new(DeviceSink::new(device)?)
+        } else {
+            Box::new(StaticColorSink::new(device))
+        };
+        animation.add_track(sink, Box::new(ColorFade::new(from, to, transition)), fps)?;
+    }
+    add_previews(&mut animation, fps, preview, |_| {
+        Ok(Some(Box::new(ColorFade::new(from, to, transition))))
+    })?;
+
+    let until = animation.clock().now() + transition.duration;
+    animation.run_until(|| cancel.is_cancelled(), Some(until));
+    if cancel.is_cancelled() {
+        println!("cancelled");
+    } else if persistence == Persistence::Stored {
+        // Release the devices before opening them again.
+        drop(animation);
+        set_color(to, persistence)?;
+    } else {
+        // The last frame was rendered before the end, finish on `to` exactly.
+        animation.flush();
     }
 
     Ok(())
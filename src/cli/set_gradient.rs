@@ -0,0 +1,16 @@
+use animation::{FrameSink, TerminalPreview};
+use devices::{self, Gradient, Layout, Persistence};
+use errors::Result;
+
+pub fn set_gradient(gradient: &Gradient, persistence: Persistence, preview: &[Layout]) -> Result<()> {
+    for device in devices::list_devices()? {
+        println!("{} {:?}", device.name(), device.set_gradient(gradient, persistence));
+    }
+
+    let terminal = TerminalPreview::new();
+    for layout in preview {
+        terminal.sink(*layout).upload(&gradient.render(layout.geometry))?;
+    }
+
+    Ok(())
+}
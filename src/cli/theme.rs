@@ -0,0 +1,23 @@
+use animation::{FrameSink, TerminalPreview};
+use devices::{self, Layout, Persistence};
+use errors::Result;
+use theme::Theme;
+
+pub fn apply_theme(theme: &Theme, persistence: Persistence, preview: &[Layout]) -> Result<()> {
+    println!("Applying {}", theme.name);
+    for device in devices::list_devices()? {
+        // Devices without custom frames can only show the background.
+        let result = match device.geometry() {
+            Some(geometry) => device.set_frame(&theme.render(geometry, device.keymap()), persistence),
+            None => device.set_color(theme.background, persistence),
+        };
+        println!("{} {:?}", device.name(), result);
+    }
+
+    let terminal = TerminalPreview::new();
+    for layout in preview {
+        terminal.sink(*layout).upload(&theme.render(layout.geometry, layout.keymap))?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,42 @@
+use super::{add_previews, run_animation};
+use animation::{Animation, DeviceSink, FrameSink, StaticColorSink, SystemClock};
+use devices::{self, Color, Layout};
+use errors::Result;
+use std::time::Duration;
+use timer::{BarRow, Countdown, Flash, Phase};
+
+pub struct TimerOptions {
+    pub row: BarRow,
+    pub empty: Color,
+    pub flash: Flash,
+}
+
+pub fn timer(phases: &[Phase], options: &TimerOptions, fps: u32, preview: &[Layout]) -> Result<()> {
+    let mut animation = Animation::new(SystemClock::new());
+    let mut length = 0.0f64;
+
+    for device in devices::list_devices()? {
+        let keymap = device.keymap();
+        // Devices without custom frames still take part in the flash.
+        let sink: Box<FrameSink> = if device.geometry().is_some() {
+            Box::new(DeviceSink::new(device)?)
+        } else {
+            Box::new(StaticColorSink::new(device))
+        };
+        let countdown = Countdown::new(phases.to_vec(), options.empty, options.flash, options.row, keymap);
+
+        length = length.max(countdown.length());
+        animation.add_track(sink, Box::new(countdown), fps)?;
+    }
+    add_previews(&mut animation, fps, preview, |layout| {
+        let countdown = Countdown::new(phases.to_vec(), options.empty, options.flash, options.row, layout.keymap);
+
+        length = length.max(countdown.length());
+        Ok(Some(Box::new(countdown)))
+    })?;
+
+    // Timers too long for a Duration run until interrupted.
+    run_animation(animation, Duration::try_from_secs_f64(length).ok(), || false);
+
+    Ok(())
+}
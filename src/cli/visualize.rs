@@ -0,0 +1,34 @@
+use super::{add_tracks, run_animation};
+use animation::{Animation, SystemClock};
+use audio::{open_pcm, AudioInput, PcmFormat, SpectrumVisualizer};
+use devices::{Color, Layout};
+use errors::Result;
+use std::fs::File;
+use std::io;
+use std::time::Duration;
+
+pub fn visualize(
+    path: Option<&str>,
+    raw_format: PcmFormat,
+    low: Color,
+    high: Color,
+    gain: f64,
+    fps: u32,
+    preview: &[Layout],
+) -> Result<()> {
+    // Files are played back as clips in real time, stdin is shown as it arrives.
+    let input = match path {
+        Some(path) if path != "-" => AudioInput::clip(open_pcm(File::open(path)?, raw_format)?)?,
+        _ => AudioInput::stream(open_pcm(io::stdin(), raw_format)?),
+    };
+    let mut animation = Animation::new(SystemClock::new());
+
+    add_tracks(&mut animation, fps, preview, |_| {
+        Ok(Some(Box::new(SpectrumVisualizer::new(input.clone(), low, high, gain))))
+    })?;
+
+    let duration = input.length().map(Duration::from_secs_f64);
+    run_animation(animation, duration, || input.is_closed());
+
+    Ok(())
+}
@@ -0,0 +1,61 @@
+use super::layout::{DeskLayout, Placement};
+use animation::{Effect, SharedEffect};
+use devices::{sample_bilinear, Frame, Layout};
+
+// One effect rendered over the whole desk, every device shows its part of it
+// through a view.
+#[derive(Clone)]
+pub struct DeskCanvas {
+    origin: (f64, f64),
+    shared: SharedEffect,
+}
+
+impl DeskCanvas {
+    pub fn new(desk: &DeskLayout, effect: Box<Effect>) -> DeskCanvas {
+        let (origin, geometry) = desk.bounds();
+
+        DeskCanvas {
+            origin,
+            shared: SharedEffect::new(effect, geometry),
+        }
+    }
+
+    // What effects are created for, they cannot rely on keys on the desk.
+    pub fn layout(desk: &DeskLayout) -> Layout {
+        Layout {
+            name: "desk",
+            geometry: desk.bounds().1,
+            keymap: None,
+        }
+    }
+
+    pub fn view(&self, placement: &Placement) -> DeskView {
+        DeskView {
+            shared: self.shared.clone(),
+            positions: placement
+                .positions
+                .iter()
+                .map(|&(x, y)| (x - self.origin.0, y - self.origin.1))
+                .collect(),
+        }
+    }
+}
+
+pub struct DeskView {
+    shared: SharedEffect,
+    positions: Vec<(f64, f64)>,
+}
+
+impl Effect for DeskView {
+    fn render(&mut self, t: f64, frame: &mut Frame) {
+        let positions = &self.positions;
+
+        self.shared.with_frame(t, |desk| {
+            for (index, &(x, y)) in positions.iter().enumerate().take(frame.geometry().len()) {
+                let (row, col) = (index / frame.cols(), index % frame.cols());
+
+                frame.set(row, col, sample_bilinear(desk, x, y));
+            }
+        });
+    }
+}
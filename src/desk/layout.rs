@@ -0,0 +1,117 @@
+use devices::{self, config_path, Geometry, Layout};
+use errors::{ErrorKind, Result, ResultExt};
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml;
+
+#[derive(Debug, Deserialize)]
+struct DeskFile {
+    #[serde(rename = "device", alias = "devices", default)]
+    devices: Vec<PlacementFile>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PlacementFile {
+    name: String,
+    x: f64,
+    y: f64,
+    pitch: Option<f64>,
+    leds: Option<Vec<[f64; 2]>>,
+}
+
+// Where the LEDs of a device are on the desk, in matrix order (row by row).
+#[derive(Debug, Clone)]
+pub struct Placement {
+    pub layout: Layout,
+    pub positions: Vec<(f64, f64)>,
+}
+
+// Devices placed in shared coordinates, measured in key widths with x to the
+// right and y downwards, so effects can run across all of them at once.
+#[derive(Debug, Clone)]
+pub struct DeskLayout {
+    pub placements: Vec<Placement>,
+}
+
+impl DeskLayout {
+    // $RAZER_TEST_DESK, or desk.toml in the user's config directory.
+    pub fn default_path() -> Option<PathBuf> {
+        config_path("RAZER_TEST_DESK", "desk.toml")
+    }
+
+    pub fn load_default() -> Result<DeskLayout> {
+        match DeskLayout::default_path() {
+            Some(ref path) if path.exists() => DeskLayout::load(path),
+            Some(path) => Err(ErrorKind::InvalidDesk(format!("'{}' does not exist", path.display())).into()),
+            None => Err(ErrorKind::InvalidDesk("no config directory".to_string()).into()),
+        }
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<DeskLayout> {
+        let path = path.as_ref();
+
+        DeskLayout::parse(&fs::read_to_string(path)?)
+            .chain_err(|| ErrorKind::InvalidDesk(format!("in '{}'", path.display())))
+    }
+
+    pub fn parse(content: &str) -> Result<DeskLayout> {
+        let file: DeskFile = toml::from_str(content)?;
+        let mut placements = Vec::new();
+
+        for placement in file.devices {
+            let layout = devices::find_layouts(&placement.name)?[0];
+            let pitch = placement.pitch.unwrap_or(1.0);
+            let positions: Vec<(f64, f64)> = match placement.leds {
+                Some(ref leds) => leds.iter().map(|led| (placement.x + led[0], placement.y + led[1])).collect(),
+                None => (0..layout.geometry.len())
+                    .map(|index| {
+                        let (row, col) = (index / layout.geometry.cols, index % layout.geometry.cols);
+                        (placement.x + col as f64 * pitch, placement.y + row as f64 * pitch)
+                    }).collect(),
+            };
+
+            if positions.len() != layout.geometry.len() {
+                return Err(ErrorKind::InvalidDesk(format!(
+                    "{} has {} LEDs, not {}",
+                    layout.name,
+                    layout.geometry.len(),
+                    positions.len()
+                )).into());
+            }
+            if positions.iter().any(|&(x, y)| !x.is_finite() || !y.is_finite()) {
+                return Err(ErrorKind::InvalidDesk(format!("{}: invalid position", layout.name)).into());
+            }
+            placements.push(Placement { layout, positions });
+        }
+        if placements.is_empty() {
+            return Err(ErrorKind::InvalidDesk("no devices".to_string()).into());
+        }
+
+        Ok(DeskLayout { placements })
+    }
+
+    pub fn placement(&self, name: &str) -> Option<&Placement> {
+        self.placements.iter().find(|placement| placement.layout.name == name)
+    }
+
+    // Top left corner of the desk and the geometry of a frame covering it with
+    // one cell per key width.
+    pub fn bounds(&self) -> ((f64, f64), Geometry) {
+        let positions = self.placements.iter().flat_map(|placement| placement.positions.iter());
+        let (mut min_x, mut min_y) = (f64::INFINITY, f64::INFINITY);
+        let (mut max_x, mut max_y) = (f64::NEG_INFINITY, f64::NEG_INFINITY);
+
+        for &(x, y) in positions {
+            min_x = min_x.min(x);
+            min_y = min_y.min(y);
+            max_x = max_x.max(x);
+            max_y = max_y.max(y);
+        }
+        let geometry = Geometry::new(
+            (max_y - min_y).ceil() as usize + 1,
+            (max_x - min_x).ceil() as usize + 1,
+        );
+
+        ((min_x, min_y), geometry)
+    }
+}
@@ -0,0 +1,5 @@
+mod canvas;
+mod layout;
+
+pub use self::canvas::*;
+pub use self::layout::*;
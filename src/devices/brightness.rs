@@ -0,0 +1,173 @@
+use errors::{ErrorKind, Result};
+use std::fmt;
+use std::str::FromStr;
+
+// Brightness as a percentage, the same on every device. Each family of
+// reports maps it to its own raw levels.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+pub struct Brightness(f64);
+
+impl Brightness {
+    pub fn from_percent(percent: f64) -> Result<Brightness> {
+        if percent.is_nan() || !(0.0..=100.0).contains(&percent) {
+            return Err(ErrorKind::InvalidBrightness(percent.to_string()).into());
+        }
+        Ok(Brightness(percent))
+    }
+
+    pub fn percent(self) -> f64 {
+        self.0
+    }
+
+    pub fn to_raw(self, scale: BrightnessScale) -> u8 {
+        let raw = (self.0 / 100.0 * f64::from(scale.max())).round();
+
+        // Anything above off should stay visible.
+        if self.0 > 0.0 {
+            raw.max(1.0) as u8
+        } else {
+            0
+        }
+    }
+
+    pub fn from_raw(raw: u8, scale: BrightnessScale) -> Brightness {
+        let max = f64::from(scale.max());
+
+        Brightness((f64::from(raw.min(scale.max())) / max * 100.0).round())
+    }
+}
+
+impl FromStr for Brightness {
+    type Err = ::errors::Error;
+
+    // "40" or "40%".
+    fn from_str(s: &str) -> Result<Brightness> {
+        match s.trim().trim_end_matches('%').parse::<f64>() {
+            Ok(percent) => {
+                Brightness::from_percent(percent).map_err(|_| ErrorKind::InvalidBrightness(s.to_string()).into())
+            }
+            Err(_) => Err(ErrorKind::InvalidBrightness(s.to_string()).into()),
+        }
+    }
+}
+
+impl fmt::Display for Brightness {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}%", self.0)
+    }
+}
+
+// The raw levels taken by each family of brightness reports. Both go up to
+// 255 on the devices known so far.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BrightnessScale {
+    // standard_set_led_brightness, per LED of keyboards.
+    Standard,
+    // extended_matrix_set_brightness, per LED of mice and newer keyboards.
+    Extended,
+}
+
+impl BrightnessScale {
+    pub fn max(self) -> u8 {
+        match self {
+            BrightnessScale::Standard => 255,
+            BrightnessScale::Extended => 255,
+        }
+    }
+}
+
+// LEDs whose brightness can be set on their own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LedZone {
+    ScrollWheel,
+    Logo,
+    Backlight,
+    LeftSide,
+    RightSide,
+}
+
+impl LedZone {
+    pub fn led_id(self) -> u8 {
+        match self {
+            LedZone::ScrollWheel => 0x01,
+            LedZone::Logo => 0x04,
+            LedZone::Backlight => 0x05,
+            LedZone::LeftSide => 0x10,
+            LedZone::RightSide => 0x11,
+        }
+    }
+}
+
+impl FromStr for LedZone {
+    type Err = ::errors::Error;
+
+    fn from_str(s: &str) -> Result<LedZone> {
+        match s {
+            "scroll-wheel" => Ok(LedZone::ScrollWheel),
+            "logo" => Ok(LedZone::Logo),
+            "backlight" => Ok(LedZone::Backlight),
+            "left-side" => Ok(LedZone::LeftSide),
+            "right-side" => Ok(LedZone::RightSide),
+            _ => Err(ErrorKind::InvalidLedZone(s.to_string()).into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_percentages() {
+        assert_eq!(Brightness::from_percent(0.0).unwrap().percent(), 0.0);
+        assert_eq!(Brightness::from_percent(42.5).unwrap().percent(), 42.5);
+        assert_eq!(Brightness::from_percent(100.0).unwrap().percent(), 100.0);
+        assert!(Brightness::from_percent(-0.1).is_err());
+        assert!(Brightness::from_percent(100.1).is_err());
+        assert!(Brightness::from_percent(f64::NAN).is_err());
+        assert!(Brightness::from_percent(f64::INFINITY).is_err());
+    }
+
+    #[test]
+    fn parses_percentages() {
+        assert_eq!("40".parse::<Brightness>().unwrap().percent(), 40.0);
+        assert_eq!(" 40% ".parse::<Brightness>().unwrap().percent(), 40.0);
+        assert_eq!("100%".parse::<Brightness>().unwrap().percent(), 100.0);
+        assert_eq!("0%".parse::<Brightness>().unwrap().percent(), 0.0);
+
+        for invalid in &["-1", "101", "nan", "NaN", "inf", "-1%", "101%", "", "%", "forty"] {
+            let error = invalid.parse::<Brightness>().unwrap_err();
+            assert_eq!(error.to_string(), ErrorKind::InvalidBrightness(invalid.to_string()).to_string());
+        }
+    }
+
+    #[test]
+    fn maps_to_raw_levels() {
+        for &scale in &[BrightnessScale::Standard, BrightnessScale::Extended] {
+            let raw = |percent: f64| Brightness::from_percent(percent).unwrap().to_raw(scale);
+
+            assert_eq!(raw(0.0), 0);
+            assert_eq!(raw(100.0), scale.max());
+            assert_eq!(raw(50.0), 128);
+            assert_eq!(raw(40.0), 102);
+            // Rounds to off, but anything above off stays visible.
+            assert_eq!(raw(0.1), 1);
+        }
+    }
+
+    #[test]
+    fn maps_from_raw_levels() {
+        for &scale in &[BrightnessScale::Standard, BrightnessScale::Extended] {
+            assert_eq!(Brightness::from_raw(0, scale).percent(), 0.0);
+            assert_eq!(Brightness::from_raw(1, scale).percent(), 0.0);
+            assert_eq!(Brightness::from_raw(128, scale).percent(), 50.0);
+            assert_eq!(Brightness::from_raw(255, scale).percent(), 100.0);
+
+            // Whole percentages survive the round trip.
+            for percent in 0..=100 {
+                let brightness = Brightness::from_percent(f64::from(percent)).unwrap();
+                assert_eq!(Brightness::from_raw(brightness.to_raw(scale), scale), brightness);
+            }
+        }
+    }
+}
@@ -0,0 +1,226 @@
+use super::config_path;
+use super::filter::ColorFilter;
+use super::razer_report::Color;
+use errors::{ErrorKind, Result, ResultExt};
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml;
+
+#[derive(Debug, Deserialize)]
+struct CalibrationFile {
+    #[serde(rename = "device", alias = "devices", default)]
+    devices: Vec<ProfileFile>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ProfileFile {
+    name: String,
+    serial: Option<String>,
+    matrix: Option<[[f64; 3]; 3]>,
+    gamma: Option<[f64; 3]>,
+}
+
+// Color correction for one device: the matrix mixes the channels (as values
+// between 0 and 1), the gamma is then applied to each channel on its own.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Calibration {
+    pub matrix: [[f64; 3]; 3],
+    pub gamma: [f64; 3],
+}
+
+impl Default for Calibration {
+    fn default() -> Calibration {
+        Calibration {
+            matrix: [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+            gamma: [1.0, 1.0, 1.0],
+        }
+    }
+}
+
+impl ColorFilter for Calibration {
+    fn apply(&self, color: Color) -> Color {
+        let input = [
+            color.red as f64 / 255.0,
+            color.green as f64 / 255.0,
+            color.blue as f64 / 255.0,
+        ];
+        let mut output = [0.0; 3];
+
+        for (channel, value) in output.iter_mut().enumerate() {
+            let row = &self.matrix[channel];
+            let mixed = row[0] * input[0] + row[1] * input[1] + row[2] * input[2];
+
+            *value = mixed.clamp(0.0, 1.0).powf(self.gamma[channel]);
+        }
+        Color::from_f64(output[0], output[1], output[2])
+    }
+}
+
+impl Calibration {
+    // The profile as a `[[device]]` entry of the calibration file.
+    pub fn to_toml(self, name: &str, serial: Option<&str>) -> String {
+        let mut entry = format!("[[device]]\nname = {:?}\n", name);
+
+        if let Some(serial) = serial {
+            writeln!(entry, "serial = {:?}", serial).unwrap();
+        }
+        let rows: Vec<String> = self
+            .matrix
+            .iter()
+            .map(|row| format!("[{:.3}, {:.3}, {:.3}]", row[0], row[1], row[2]))
+            .collect();
+        writeln!(entry, "matrix = [{}]", rows.join(", ")).unwrap();
+        writeln!(
+            entry,
+            "gamma = [{:.3}, {:.3}, {:.3}]",
+            self.gamma[0], self.gamma[1], self.gamma[2]
+        ).unwrap();
+        entry
+    }
+
+    fn is_valid(&self) -> bool {
+        self.matrix.iter().flat_map(|row| row.iter()).all(|value| value.is_finite())
+            && self.gamma.iter().all(|gamma| gamma.is_finite() && *gamma > 0.0)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Profile {
+    name: String,
+    serial: Option<String>,
+    calibration: Calibration,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CalibrationProfiles {
+    profiles: Vec<Profile>,
+}
+
+impl CalibrationProfiles {
+    // $RAZER_TEST_CALIBRATION, or calibration.toml in the user's config directory.
+    pub fn default_path() -> Option<PathBuf> {
+        config_path("RAZER_TEST_CALIBRATION", "calibration.toml")
+    }
+
+    // Profiles from the default path, none if there is no calibration file.
+    pub fn load_default() -> Result<CalibrationProfiles> {
+        match CalibrationProfiles::default_path() {
+            Some(ref path) if path.exists() => CalibrationProfiles::load(path),
+            _ => Ok(CalibrationProfiles::default()),
+        }
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<CalibrationProfiles> {
+        let path = path.as_ref();
+
+        CalibrationProfiles::parse(&fs::read_to_string(path)?)
+            .chain_err(|| ErrorKind::InvalidCalibration(format!("in '{}'", path.display())))
+    }
+
+    pub fn parse(content: &str) -> Result<CalibrationProfiles> {
+        let file: CalibrationFile = toml::from_str(content)?;
+        let mut profiles = Vec::new();
+
+        for profile in file.devices {
+            let default = Calibration::default();
+            let calibration = Calibration {
+                matrix: profile.matrix.unwrap_or(default.matrix),
+                gamma: profile.gamma.unwrap_or(default.gamma),
+            };
+
+            if !calibration.is_valid() {
+                return Err(ErrorKind::InvalidCalibration(format!(
+                    "'{}': gamma must be positive and all values finite",
+                    profile.name
+                )).into());
+            }
+            profiles.push(Profile {
+                name: profile.name,
+                serial: profile.serial,
+                calibration,
+            });
+        }
+
+        Ok(CalibrationProfiles { profiles })
+    }
+
+    // Replaces the profile for exactly this name and serial, or adds it.
+    pub fn insert(&mut self, name: &str, serial: Option<&str>, calibration: Calibration) {
+        self.profiles
+            .retain(|profile| !(profile.name.eq_ignore_ascii_case(name) && profile.serial.as_deref() == serial));
+        self.profiles.push(Profile {
+            name: name.to_string(),
+            serial: serial.map(|serial| serial.to_string()),
+            calibration,
+        });
+    }
+
+    // All profiles in the format of the calibration file.
+    pub fn to_toml(&self) -> String {
+        let entries: Vec<String> = self
+            .profiles
+            .iter()
+            .map(|profile| profile.calibration.to_toml(&profile.name, profile.serial.as_deref()))
+            .collect();
+        entries.join("\n")
+    }
+
+    pub fn has_serials(&self) -> bool {
+        self.profiles.iter().any(|profile| profile.serial.is_some())
+    }
+
+    // A profile for this very device wins over one for its model, which is
+    // matched like timeline tracks (case-insensitive part of the name). Later
+    // entries override earlier ones.
+    pub fn find(&self, device_name: &str, serial: Option<&str>) -> Option<Calibration> {
+        let lowercase = device_name.to_lowercase();
+        let matches_name = |profile: &&Profile| lowercase.contains(&profile.name.to_lowercase());
+
+        self.profiles
+            .iter()
+            .rev()
+            .filter(matches_name)
+            .find(|profile| profile.serial.is_some() && profile.serial.as_deref() == serial)
+            .or_else(|| {
+                self.profiles
+                    .iter()
+                    .rev()
+                    .filter(matches_name)
+                    .find(|profile| profile.serial.is_none())
+            }).map(|profile| profile.calibration)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROFILES: &str = r#"
+[[device]]
+name = "Razer Mamba"
+gamma = [2.0, 2.0, 2.0]
+
+[[device]]
+name = "Razer Mamba"
+serial = "PM1234"
+gamma = [1.5, 1.5, 1.5]
+"#;
+
+    #[test]
+    fn replaces_the_saved_profile() {
+        let mut profiles = CalibrationProfiles::parse(PROFILES).unwrap();
+        let calibration = Calibration {
+            gamma: [0.5, 0.5, 0.5],
+            ..Calibration::default()
+        };
+
+        profiles.insert("Razer Mamba", Some("PM1234"), calibration);
+        profiles.insert("Razer Mamba", Some("PM1234"), calibration);
+        let saved = CalibrationProfiles::parse(&profiles.to_toml()).unwrap();
+
+        assert_eq!(saved.profiles.len(), 2);
+        assert_eq!(saved.find("Razer Mamba", Some("PM1234")), Some(calibration));
+        assert_eq!(saved.find("Razer Mamba", None).unwrap().gamma, [2.0, 2.0, 2.0]);
+    }
+}
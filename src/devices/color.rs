@@ -0,0 +1,707 @@
+use super::razer_report::Color;
+use errors::{ErrorKind, Result};
+use std::str::FromStr;
+
+fn invalid(color_str: &str, reason: String) -> ::errors::Error {
+    ErrorKind::InvalidColorFormat(color_str.to_string(), reason).into()
+}
+
+fn to_u8(value: f64) -> u8 {
+    (value * 255.0).round().clamp(0.0, 255.0) as u8
+}
+
+// sRGB transfer function
+fn to_linear(value: u8) -> f64 {
+    let value = value as f64 / 255.0;
+
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn from_linear(value: f64) -> u8 {
+    let value = value.clamp(0.0, 1.0);
+
+    to_u8(if value <= 0.003_130_8 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    })
+}
+
+// How colors are blended into each other, e.g. in gradients and fades.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Interpolation {
+    // Straight on the sRGB values, like most tools do.
+    #[default]
+    Rgb,
+    // In linear light, mixes look brighter in the middle.
+    Linear,
+    // Along the shorter way around the hue circle.
+    Hsv,
+    // In the Oklab color space, evenly spaced to the eye.
+    Perceptual,
+}
+
+impl FromStr for Interpolation {
+    type Err = ::errors::Error;
+
+    fn from_str(s: &str) -> Result<Interpolation> {
+        match s {
+            "rgb" => Ok(Interpolation::Rgb),
+            "linear" => Ok(Interpolation::Linear),
+            "hsv" => Ok(Interpolation::Hsv),
+            "perceptual" => Ok(Interpolation::Perceptual),
+            _ => Err(ErrorKind::InvalidParameter("interpolation".to_string(), s.to_string()).into()),
+        }
+    }
+}
+
+impl Color {
+    // Accepts "r,g,b", "#rrggbb", "#rgb", CSS color names, "hsv(h, s%, v%)",
+    // "hsl(h, s%, l%)" and color temperatures like "6500K".
+    pub fn parse(color_str: &str) -> Result<Color> {
+        let trimmed = color_str.trim();
+        let lowercase = trimmed.to_lowercase();
+
+        if let Some(digits) = lowercase.strip_prefix('#') {
+            parse_hex(color_str, digits)
+        } else if lowercase.contains(',') && !lowercase.contains('(') {
+            parse_rgb(color_str, trimmed)
+        } else if let Some(args) = function_args(&lowercase, "hsv") {
+            let (hue, saturation, value) = parse_hue_args(color_str, args, "value")?;
+            Ok(Color::from_hsv(hue, saturation, value))
+        } else if let Some(args) = function_args(&lowercase, "hsl") {
+            let (hue, saturation, lightness) = parse_hue_args(color_str, args, "lightness")?;
+            Ok(Color::from_hsl(hue, saturation, lightness))
+        } else if lowercase.ends_with('k') && lowercase[..lowercase.len() - 1].parse::<f64>().is_ok() {
+            let kelvin = lowercase[..lowercase.len() - 1].parse::<f64>().unwrap();
+
+            if !(1000.0..=40000.0).contains(&kelvin) {
+                return Err(invalid(color_str, "temperature must be between 1000K and 40000K".to_string()));
+            }
+            Ok(Color::from_kelvin(kelvin))
+        } else {
+            match CSS_COLORS.binary_search_by_key(&lowercase.as_str(), |&(name, _)| name) {
+                Ok(index) => {
+                    let rgb = CSS_COLORS[index].1;
+                    Ok(Color::new((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8))
+                }
+                Err(_) => Err(invalid(color_str, "unknown color name".to_string())),
+            }
+        }
+    }
+
+    // Hue in degrees, saturation and value between 0 and 1.
+    pub fn from_hsv(hue: f64, saturation: f64, value: f64) -> Color {
+        let hue = hue.rem_euclid(360.0) / 60.0;
+        let chroma = value * saturation;
+        let x = chroma * (1.0 - (hue % 2.0 - 1.0).abs());
+        let (r, g, b) = match hue as u32 {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+        let m = value - chroma;
+
+        Color::new(to_u8(r + m), to_u8(g + m), to_u8(b + m))
+    }
+
+    // Hue in degrees, saturation and lightness between 0 and 1.
+    pub fn from_hsl(hue: f64, saturation: f64, lightness: f64) -> Color {
+        let value = lightness + saturation * lightness.min(1.0 - lightness);
+        let saturation = if value > 0.0 {
+            2.0 * (1.0 - lightness / value)
+        } else {
+            0.0
+        };
+
+        Color::from_hsv(hue, saturation, value)
+    }
+
+    // Approximation of black body radiation, good from 1000K to 40000K.
+    pub fn from_kelvin(kelvin: f64) -> Color {
+        let t = kelvin / 100.0;
+        let red = if t <= 66.0 {
+            255.0
+        } else {
+            329.698_727_446 * (t - 60.0).powf(-0.133_204_759_2)
+        };
+        let green = if t <= 66.0 {
+            99.470_802_586_1 * t.ln() - 161.119_568_166_1
+        } else {
+            288.122_169_528_3 * (t - 60.0).powf(-0.075_514_849_2)
+        };
+        let blue = if t >= 66.0 {
+            255.0
+        } else if t <= 19.0 {
+            0.0
+        } else {
+            138.517_731_223_1 * (t - 10.0).ln() - 305.044_792_730_7
+        };
+
+        Color::new(to_u8(red / 255.0), to_u8(green / 255.0), to_u8(blue / 255.0))
+    }
+}
+
+impl Color {
+    // Components between 0 and 1, clamped.
+    pub fn from_f64(red: f64, green: f64, blue: f64) -> Color {
+        Color::new(to_u8(red), to_u8(green), to_u8(blue))
+    }
+
+    pub fn from_linear(red: f64, green: f64, blue: f64) -> Color {
+        Color::new(from_linear(red), from_linear(green), from_linear(blue))
+    }
+
+    pub fn to_linear(self) -> (f64, f64, f64) {
+        (to_linear(self.red), to_linear(self.green), to_linear(self.blue))
+    }
+
+    // Hue in degrees, saturation and value between 0 and 1.
+    pub fn to_hsv(self) -> (f64, f64, f64) {
+        let (r, g, b) = (
+            self.red as f64 / 255.0,
+            self.green as f64 / 255.0,
+            self.blue as f64 / 255.0,
+        );
+        let max = r.max(g).max(b);
+        let chroma = max - r.min(g).min(b);
+        let hue = if chroma == 0.0 {
+            0.0
+        } else if max == r {
+            60.0 * ((g - b) / chroma).rem_euclid(6.0)
+        } else if max == g {
+            60.0 * ((b - r) / chroma + 2.0)
+        } else {
+            60.0 * ((r - g) / chroma + 4.0)
+        };
+        let saturation = if max > 0.0 { chroma / max } else { 0.0 };
+
+        (hue, saturation, max)
+    }
+
+    // Hue in degrees, saturation and lightness between 0 and 1.
+    pub fn to_hsl(self) -> (f64, f64, f64) {
+        let (hue, saturation, value) = self.to_hsv();
+        let lightness = value * (1.0 - saturation / 2.0);
+        let saturation = if lightness > 0.0 && lightness < 1.0 {
+            (value - lightness) / lightness.min(1.0 - lightness)
+        } else {
+            0.0
+        };
+
+        (hue, saturation, lightness)
+    }
+
+    fn to_oklab(self) -> (f64, f64, f64) {
+        let (r, g, b) = self.to_linear();
+        let l = (0.412_221_470_8 * r + 0.536_332_536_3 * g + 0.051_445_992_9 * b).cbrt();
+        let m = (0.211_903_498_2 * r + 0.680_699_545_1 * g + 0.107_396_956_6 * b).cbrt();
+        let s = (0.088_302_461_9 * r + 0.281_718_837_6 * g + 0.629_978_700_5 * b).cbrt();
+
+        (
+            0.210_454_255_3 * l + 0.793_617_785_0 * m - 0.004_072_046_8 * s,
+            1.977_998_495_1 * l - 2.428_592_205_0 * m + 0.450_593_709_9 * s,
+            0.025_904_037_1 * l + 0.782_771_766_2 * m - 0.808_675_766_0 * s,
+        )
+    }
+
+    fn from_oklab(lightness: f64, a: f64, b: f64) -> Color {
+        let l = (lightness + 0.396_337_777_4 * a + 0.215_803_757_3 * b).powi(3);
+        let m = (lightness - 0.105_561_345_8 * a - 0.063_854_172_8 * b).powi(3);
+        let s = (lightness - 0.089_484_177_5 * a - 1.291_485_548_0 * b).powi(3);
+
+        Color::from_linear(
+            4.076_741_662_1 * l - 3.307_711_591_3 * m + 0.230_969_929_2 * s,
+            -1.268_438_004_6 * l + 2.609_757_401_1 * m - 0.341_319_396_5 * s,
+            -0.004_196_086_3 * l - 0.703_418_614_7 * m + 1.707_614_701_0 * s,
+        )
+    }
+
+    // Multiplies every channel, e.g. for fading an LED out.
+    pub fn scale(self, factor: f64) -> Color {
+        Color::new(
+            to_u8(self.red as f64 / 255.0 * factor),
+            to_u8(self.green as f64 / 255.0 * factor),
+            to_u8(self.blue as f64 / 255.0 * factor),
+        )
+    }
+
+    // Scales by a perceived brightness between 0 and 1. LED brightness is
+    // linear in the channel values, so halving them looks much brighter than half.
+    pub fn dim(self, brightness: f64) -> Color {
+        self.scale(brightness.clamp(0.0, 1.0).powf(2.2))
+    }
+
+    // Applies `value ^ gamma` to every channel (as fractions of 255).
+    pub fn gamma(self, gamma: f64) -> Color {
+        let apply = |value: u8| to_u8((value as f64 / 255.0).powf(gamma));
+
+        Color::new(apply(self.red), apply(self.green), apply(self.blue))
+    }
+
+    // Straight sRGB interpolation from self (t = 0) to other (t = 1).
+    pub fn mix(self, other: Color, t: f64) -> Color {
+        self.interpolate(other, t, Interpolation::Rgb)
+    }
+
+    pub fn interpolate(self, other: Color, t: f64, interpolation: Interpolation) -> Color {
+        let t = t.clamp(0.0, 1.0);
+        let lerp = |a: f64, b: f64| a + (b - a) * t;
+
+        match interpolation {
+            Interpolation::Rgb => Color::new(
+                (lerp(self.red as f64, other.red as f64)).round() as u8,
+                (lerp(self.green as f64, other.green as f64)).round() as u8,
+                (lerp(self.blue as f64, other.blue as f64)).round() as u8,
+            ),
+            Interpolation::Linear => {
+                let (from, to) = (self.to_linear(), other.to_linear());
+                Color::from_linear(lerp(from.0, to.0), lerp(from.1, to.1), lerp(from.2, to.2))
+            }
+            Interpolation::Hsv => {
+                let (from, to) = (self.to_hsv(), other.to_hsv());
+                // Gray has no hue, keep the other one so the hue does not swing around.
+                let from_hue = if from.1 == 0.0 { to.0 } else { from.0 };
+                let to_hue = if to.1 == 0.0 { from_hue } else { to.0 };
+                let delta = (to_hue - from_hue + 540.0).rem_euclid(360.0) - 180.0;
+
+                Color::from_hsv(from_hue + delta * t, lerp(from.1, to.1), lerp(from.2, to.2))
+            }
+            Interpolation::Perceptual => {
+                let (from, to) = (self.to_oklab(), other.to_oklab());
+                Color::from_oklab(lerp(from.0, to.0), lerp(from.1, to.1), lerp(from.2, to.2))
+            }
+        }
+    }
+
+    // Samples evenly spaced color stops at t between 0 and 1.
+    pub fn gradient(stops: &[Color], t: f64, interpolation: Interpolation) -> Color {
+        match stops.len() {
+            0 => Color::default(),
+            1 => stops[0],
+            count => {
+                let position = t.clamp(0.0, 1.0) * (count - 1) as f64;
+                let index = (position as usize).min(count - 2);
+
+                stops[index].interpolate(stops[index + 1], position - index as f64, interpolation)
+            }
+        }
+    }
+}
+
+// Splits a list of colors at whitespace or ';', but not inside "hsv(..)" and "hsl(..)".
+pub fn split_colors(list: &str) -> Vec<&str> {
+    let mut colors = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+
+    for (i, c) in list.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ';' => (),
+            _ if c.is_whitespace() => (),
+            _ => continue,
+        }
+        if depth == 0 && (c == ';' || c.is_whitespace()) {
+            colors.push(&list[start..i]);
+            start = i + c.len_utf8();
+        }
+    }
+    colors.push(&list[start..]);
+
+    colors.into_iter().filter(|color| !color.is_empty()).collect()
+}
+
+fn parse_rgb(color_str: &str, trimmed: &str) -> Result<Color> {
+    let parts: Vec<&str> = trimmed.split(',').map(str::trim).collect();
+
+    if parts.len() != 3 {
+        return Err(invalid(color_str, format!("expected 3 components, got {}", parts.len())));
+    }
+    let mut components = [0u8; 3];
+    for (component, part) in components.iter_mut().zip(parts) {
+        *component = part
+            .parse::<u8>()
+            .map_err(|_| invalid(color_str, format!("'{}' is not a number from 0 to 255", part)))?;
+    }
+
+    Ok(Color::new(components[0], components[1], components[2]))
+}
+
+fn parse_hex(color_str: &str, digits: &str) -> Result<Color> {
+    if let Some(c) = digits.chars().find(|c| !c.is_ascii_hexdigit()) {
+        return Err(invalid(color_str, format!("'{}' is not a hex digit", c)));
+    }
+    let value = |range: ::std::ops::Range<usize>| u8::from_str_radix(&digits[range], 16).unwrap();
+
+    match digits.len() {
+        3 => Ok(Color::new(value(0..1) * 17, value(1..2) * 17, value(2..3) * 17)),
+        6 => Ok(Color::new(value(0..2), value(2..4), value(4..6))),
+        count => Err(invalid(color_str, format!("expected 3 or 6 hex digits, got {}", count))),
+    }
+}
+
+fn function_args<'a>(lowercase: &'a str, name: &str) -> Option<&'a str> {
+    lowercase
+        .strip_prefix(name)
+        .map(str::trim_start)
+        .and_then(|rest| rest.strip_prefix('('))
+        .and_then(|rest| rest.strip_suffix(')'))
+}
+
+// "h, s%, v%" or "h s% v%": hue in degrees (an optional "deg" suffix), the
+// others as percentages (the '%' is optional), returned as fractions.
+fn parse_hue_args(color_str: &str, args: &str, third: &str) -> Result<(f64, f64, f64)> {
+    let parts: Vec<&str> = args
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|part| !part.is_empty())
+        .collect();
+
+    if parts.len() != 3 {
+        return Err(invalid(color_str, format!("expected 3 arguments, got {}", parts.len())));
+    }
+    let number = |part: &str, suffix: &str, max: f64, what: &str| {
+        part.trim_end_matches(suffix)
+            .parse::<f64>()
+            .ok()
+            .filter(|value| (0.0..=max).contains(value))
+            .ok_or_else(|| invalid(color_str, format!("{} '{}' must be between 0 and {}", what, part, max)))
+    };
+
+    Ok((
+        number(parts[0], "deg", 360.0, "hue")?,
+        number(parts[1], "%", 100.0, "saturation")? / 100.0,
+        number(parts[2], "%", 100.0, third)? / 100.0,
+    ))
+}
+
+// CSS named colors, sorted by name.
+static CSS_COLORS: &[(&str, u32)] = &[
+    ("aliceblue", 0xf0f8ff),
+    ("antiquewhite", 0xfaebd7),
+    ("aqua", 0x00ffff),
+    ("aquamarine", 0x7fffd4),
+    ("azure", 0xf0ffff),
+    ("beige", 0xf5f5dc),
+    ("bisque", 0xffe4c4),
+    ("black", 0x000000),
+    ("blanchedalmond", 0xffebcd),
+    ("blue", 0x0000ff),
+    ("blueviolet", 0x8a2be2),
+    ("brown", 0xa52a2a),
+    ("burlywood", 0xdeb887),
+    ("cadetblue", 0x5f9ea0),
+    ("chartreuse", 0x7fff00),
+    ("chocolate", 0xd2691e),
+    ("coral", 0xff7f50),
+    ("cornflowerblue", 0x6495ed),
+    ("cornsilk", 0xfff8dc),
+    ("crimson", 0xdc143c),
+    ("cyan", 0x00ffff),
+    ("darkblue", 0x00008b),
+    ("darkcyan", 0x008b8b),
+    ("darkgoldenrod", 0xb8860b),
+    ("darkgray", 0xa9a9a9),
+    ("darkgreen", 0x006400),
+    ("darkgrey", 0xa9a9a9),
+    ("darkkhaki", 0xbdb76b),
+    ("darkmagenta", 0x8b008b),
+    ("darkolivegreen", 0x556b2f),
+    ("darkorange", 0xff8c00),
+    ("darkorchid", 0x9932cc),
+    ("darkred", 0x8b0000),
+    ("darksalmon", 0xe9967a),
+    ("darkseagreen", 0x8fbc8f),
+    ("darkslateblue", 0x483d8b),
+    ("darkslategray", 0x2f4f4f),
+    ("darkslategrey", 0x2f4f4f),
+    ("darkturquoise", 0x00ced1),
+    ("darkviolet", 0x9400d3),
+    ("deeppink", 0xff1493),
+    ("deepskyblue", 0x00bfff),
+    ("dimgray", 0x696969),
+    ("dimgrey", 0x696969),
+    ("dodgerblue", 0x1e90ff),
+    ("firebrick", 0xb22222),
+    ("floralwhite", 0xfffaf0),
+    ("forestgreen", 0x228b22),
+    ("fuchsia", 0xff00ff),
+    ("gainsboro", 0xdcdcdc),
+    ("ghostwhite", 0xf8f8ff),
+    ("gold", 0xffd700),
+    ("goldenrod", 0xdaa520),
+    ("gray", 0x808080),
+    ("green", 0x008000),
+    ("greenyellow", 0xadff2f),
+    ("grey", 0x808080),
+    ("honeydew", 0xf0fff0),
+    ("hotpink", 0xff69b4),
+    ("indianred", 0xcd5c5c),
+    ("indigo", 0x4b0082),
+    ("ivory", 0xfffff0),
+    ("khaki", 0xf0e68c),
+    ("lavender", 0xe6e6fa),
+    ("lavenderblush", 0xfff0f5),
+    ("lawngreen", 0x7cfc00),
+    ("lemonchiffon", 0xfffacd),
+    ("lightblue", 0xadd8e6),
+    ("lightcoral", 0xf08080),
+    ("lightcyan", 0xe0ffff),
+    ("lightgoldenrodyellow", 0xfafad2),
+    ("lightgray", 0xd3d3d3),
+    ("lightgreen", 0x90ee90),
+    ("lightgrey", 0xd3d3d3),
+    ("lightpink", 0xffb6c1),
+    ("lightsalmon", 0xffa07a),
+    ("lightseagreen", 0x20b2aa),
+    ("lightskyblue", 0x87cefa),
+    ("lightslategray", 0x778899),
+    ("lightslategrey", 0x778899),
+    ("lightsteelblue", 0xb0c4de),
+    ("lightyellow", 0xffffe0),
+    ("lime", 0x00ff00),
+    ("limegreen", 0x32cd32),
+    ("linen", 0xfaf0e6),
+    ("magenta", 0xff00ff),
+    ("maroon", 0x800000),
+    ("mediumaquamarine", 0x66cdaa),
+    ("mediumblue", 0x0000cd),
+    ("mediumorchid", 0xba55d3),
+    ("mediumpurple", 0x9370db),
+    ("mediumseagreen", 0x3cb371),
+    ("mediumslateblue", 0x7b68ee),
+    ("mediumspringgreen", 0x00fa9a),
+    ("mediumturquoise", 0x48d1cc),
+    ("mediumvioletred", 0xc71585),
+    ("midnightblue", 0x191970),
+    ("mintcream", 0xf5fffa),
+    ("mistyrose", 0xffe4e1),
+    ("moccasin", 0xffe4b5),
+    ("navajowhite", 0xffdead),
+    ("navy", 0x000080),
+    ("oldlace", 0xfdf5e6),
+    ("olive", 0x808000),
+    ("olivedrab", 0x6b8e23),
+    ("orange", 0xffa500),
+    ("orangered", 0xff4500),
+    ("orchid", 0xda70d6),
+    ("palegoldenrod", 0xeee8aa),
+    ("palegreen", 0x98fb98),
+    ("paleturquoise", 0xafeeee),
+    ("palevioletred", 0xdb7093),
+    ("papayawhip", 0xffefd5),
+    ("peachpuff", 0xffdab9),
+    ("peru", 0xcd853f),
+    ("pink", 0xffc0cb),
+    ("plum", 0xdda0dd),
+    ("powderblue", 0xb0e0e6),
+    ("purple", 0x800080),
+    ("rebeccapurple", 0x663399),
+    ("red", 0xff0000),
+    ("rosybrown", 0xbc8f8f),
+    ("royalblue", 0x4169e1),
+    ("saddlebrown", 0x8b4513),
+    ("salmon", 0xfa8072),
+    ("sandybrown", 0xf4a460),
+    ("seagreen", 0x2e8b57),
+    ("seashell", 0xfff5ee),
+    ("sienna", 0xa0522d),
+    ("silver", 0xc0c0c0),
+    ("skyblue", 0x87ceeb),
+    ("slateblue", 0x6a5acd),
+    ("slategray", 0x708090),
+    ("slategrey", 0x708090),
+    ("snow", 0xfffafa),
+    ("springgreen", 0x00ff7f),
+    ("steelblue", 0x4682b4),
+    ("tan", 0xd2b48c),
+    ("teal", 0x008080),
+    ("thistle", 0xd8bfd8),
+    ("tomato", 0xff6347),
+    ("turquoise", 0x40e0d0),
+    ("violet", 0xee82ee),
+    ("wheat", 0xf5deb3),
+    ("white", 0xffffff),
+    ("whitesmoke", 0xf5f5f5),
+    ("yellow", 0xffff00),
+    ("yellowgreen", 0x9acd32),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(color_str: &str) -> String {
+        Color::parse(color_str).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn parses_rgb_and_hex() {
+        assert_eq!(Color::parse("255,128,0").unwrap(), Color::new(255, 128, 0));
+        assert_eq!(Color::parse(" 255, 128 ,0 ").unwrap(), Color::new(255, 128, 0));
+        assert_eq!(Color::parse("#ff8000").unwrap(), Color::new(255, 128, 0));
+        assert_eq!(Color::parse("#FF8000").unwrap(), Color::new(255, 128, 0));
+        assert_eq!(Color::parse("#f80").unwrap(), Color::new(255, 136, 0));
+    }
+
+    #[test]
+    fn parses_css_names() {
+        assert_eq!(Color::parse("red").unwrap(), Color::new(255, 0, 0));
+        assert_eq!(Color::parse("RebeccaPurple").unwrap(), Color::new(0x66, 0x33, 0x99));
+        assert!(CSS_COLORS.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    }
+
+    #[test]
+    fn parses_hsv_and_hsl() {
+        assert_eq!(Color::parse("hsv(120, 100%, 100%)").unwrap(), Color::new(0, 255, 0));
+        assert_eq!(Color::parse("HSV(0deg 100 50)").unwrap(), Color::new(128, 0, 0));
+        assert_eq!(Color::parse("hsl(240, 100%, 50%)").unwrap(), Color::new(0, 0, 255));
+        assert_eq!(Color::parse("hsl (0, 0%, 100%)").unwrap(), Color::new(255, 255, 255));
+    }
+
+    #[test]
+    fn parses_temperatures() {
+        let candle = Color::parse("1900K").unwrap();
+        assert!(candle.red == 255 && candle.green < 160 && candle.blue < 60);
+        let daylight = Color::parse("6500k").unwrap();
+        assert!(daylight.red > 240 && daylight.green > 240 && daylight.blue > 230);
+    }
+
+    #[test]
+    fn rejects_malformed_colors() {
+        assert_eq!(error("1,2"), "invalid color '1,2': expected 3 components, got 2");
+        assert_eq!(error("1,2,300"), "invalid color '1,2,300': '300' is not a number from 0 to 255");
+        assert_eq!(error("1,-2,3"), "invalid color '1,-2,3': '-2' is not a number from 0 to 255");
+        assert_eq!(error("#12345"), "invalid color '#12345': expected 3 or 6 hex digits, got 5");
+        assert_eq!(error("#ggg"), "invalid color '#ggg': 'g' is not a hex digit");
+        assert_eq!(error("hsv(1, 2)"), "invalid color 'hsv(1, 2)': expected 3 arguments, got 2");
+        assert_eq!(error("hsv(400,1,1)"), "invalid color 'hsv(400,1,1)': hue '400' must be between 0 and 360");
+        assert_eq!(
+            error("hsl(0,150%,1)"),
+            "invalid color 'hsl(0,150%,1)': saturation '150%' must be between 0 and 100"
+        );
+        assert_eq!(error("hsl(0,1,x)"), "invalid color 'hsl(0,1,x)': lightness 'x' must be between 0 and 100");
+        assert_eq!(error("500K"), "invalid color '500K': temperature must be between 1000K and 40000K");
+        assert_eq!(error("notacolor"), "invalid color 'notacolor': unknown color name");
+        assert_eq!(error(""), "invalid color '': unknown color name");
+    }
+
+    fn samples() -> Vec<Color> {
+        let steps = [0u8, 1, 17, 64, 128, 200, 254, 255];
+        let mut colors = Vec::new();
+        for &red in &steps {
+            for &green in &steps {
+                for &blue in &steps {
+                    colors.push(Color::new(red, green, blue));
+                }
+            }
+        }
+        colors
+    }
+
+    #[test]
+    fn round_trips_color_spaces() {
+        for color in samples() {
+            let (hue, saturation, value) = color.to_hsv();
+            assert_eq!(Color::from_hsv(hue, saturation, value), color);
+            let (hue, saturation, lightness) = color.to_hsl();
+            assert_eq!(Color::from_hsl(hue, saturation, lightness), color);
+            let (red, green, blue) = color.to_linear();
+            assert_eq!(Color::from_linear(red, green, blue), color);
+            let (lightness, a, b) = color.to_oklab();
+            assert_eq!(Color::from_oklab(lightness, a, b), color);
+        }
+    }
+
+    #[test]
+    fn converts_to_hsv_and_hsl() {
+        assert_eq!(Color::new(255, 0, 0).to_hsv(), (0.0, 1.0, 1.0));
+        assert_eq!(Color::new(0, 255, 0).to_hsv(), (120.0, 1.0, 1.0));
+        assert_eq!(Color::new(0, 0, 255).to_hsl(), (240.0, 1.0, 0.5));
+        assert_eq!(Color::new(255, 255, 255).to_hsl(), (0.0, 0.0, 1.0));
+        assert_eq!(Color::new(0, 0, 0).to_hsv(), (0.0, 0.0, 0.0));
+        // Hues wrap around.
+        assert_eq!(Color::from_hsv(480.0, 1.0, 1.0), Color::from_hsv(120.0, 1.0, 1.0));
+        assert_eq!(Color::from_hsv(-120.0, 1.0, 1.0), Color::new(0, 0, 255));
+    }
+
+    #[test]
+    fn interpolates_endpoints_and_midpoints() {
+        let (black, white) = (Color::new(0, 0, 0), Color::new(255, 255, 255));
+        let (red, blue) = (Color::new(255, 0, 0), Color::new(0, 0, 255));
+        let all = [
+            Interpolation::Rgb,
+            Interpolation::Linear,
+            Interpolation::Hsv,
+            Interpolation::Perceptual,
+        ];
+
+        for &interpolation in &all {
+            assert_eq!(red.interpolate(blue, 0.0, interpolation), red);
+            assert_eq!(red.interpolate(blue, 1.0, interpolation), blue);
+            assert_eq!(red.interpolate(blue, -1.0, interpolation), red);
+            assert_eq!(red.interpolate(blue, 2.0, interpolation), blue);
+        }
+        assert_eq!(black.mix(white, 0.5), Color::new(128, 128, 128));
+        assert_eq!(black.interpolate(white, 0.5, Interpolation::Linear), Color::new(188, 188, 188));
+        // The shorter way from red (0) to blue (240) goes through magenta (300).
+        assert_eq!(red.interpolate(blue, 0.5, Interpolation::Hsv), Color::new(255, 0, 255));
+        // Gray keeps the hue of the other end instead of swinging through red.
+        assert_eq!(black.interpolate(blue, 0.5, Interpolation::Hsv), Color::new(64, 64, 128));
+
+        let perceptual = black.interpolate(white, 0.5, Interpolation::Perceptual);
+        assert!(perceptual.red == perceptual.green && perceptual.green == perceptual.blue);
+        // Oklab puts the middle gray darker than sRGB does, at a lightness of 0.5.
+        assert!(perceptual.red > 64 && perceptual.red < 128);
+    }
+
+    #[test]
+    fn samples_gradients() {
+        let stops = [Color::new(255, 0, 0), Color::new(0, 255, 0), Color::new(0, 0, 255)];
+
+        assert_eq!(Color::gradient(&stops, 0.0, Interpolation::Rgb), stops[0]);
+        assert_eq!(Color::gradient(&stops, 0.5, Interpolation::Rgb), stops[1]);
+        assert_eq!(Color::gradient(&stops, 1.0, Interpolation::Rgb), stops[2]);
+        assert_eq!(Color::gradient(&stops, 0.25, Interpolation::Rgb), Color::new(128, 128, 0));
+        assert_eq!(Color::gradient(&stops, 0.75, Interpolation::Rgb), Color::new(0, 128, 128));
+        assert_eq!(Color::gradient(&stops, -0.5, Interpolation::Rgb), stops[0]);
+        assert_eq!(Color::gradient(&stops, 1.5, Interpolation::Rgb), stops[2]);
+        assert_eq!(Color::gradient(&stops[..1], 0.7, Interpolation::Rgb), stops[0]);
+        assert_eq!(Color::gradient(&[], 0.7, Interpolation::Rgb), Color::default());
+    }
+
+    #[test]
+    fn scales_dims_and_clamps() {
+        let color = Color::new(200, 128, 0);
+
+        assert_eq!(color.scale(0.5), Color::new(100, 64, 0));
+        assert_eq!(color.scale(2.0), Color::new(255, 255, 0));
+        assert_eq!(color.scale(-1.0), Color::new(0, 0, 0));
+        assert_eq!(color.dim(1.0), color);
+        assert_eq!(color.dim(2.0), color);
+        assert_eq!(color.dim(-1.0), Color::new(0, 0, 0));
+        // Half the perceived brightness is about a fifth of the channel values.
+        assert_eq!(Color::new(255, 255, 255).dim(0.5), Color::new(55, 55, 55));
+        assert_eq!(color.gamma(1.0), color);
+        assert_eq!(Color::new(255, 128, 0).gamma(2.0), Color::new(255, 64, 0));
+        assert_eq!(Color::new(255, 64, 0).gamma(0.5), Color::new(255, 128, 0));
+        assert_eq!(Color::from_f64(-0.5, 0.5, 2.0), Color::new(0, 128, 255));
+        assert_eq!(Color::from_linear(-1.0, 0.0, 2.0), Color::new(0, 0, 255));
+    }
+
+    #[test]
+    fn splits_color_lists() {
+        assert_eq!(
+            split_colors("red  #00f;hsv(120, 100%, 50%) 1,2,3"),
+            vec!["red", "#00f", "hsv(120, 100%, 50%)", "1,2,3"]
+        );
+    }
+}
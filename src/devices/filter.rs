@@ -0,0 +1,75 @@
+use super::brightness::{Brightness, BrightnessScale, LedZone};
+use super::frame::{Frame, Geometry};
+use super::keymap::Keymap;
+use super::persistence::Persistence;
+use super::razer_report::Color;
+use super::Device;
+use errors::Result;
+use hidapi::HidDevice;
+
+// Changes colors on their way to a device, e.g. calibration.
+pub trait ColorFilter {
+    fn apply(&self, color: Color) -> Color;
+}
+
+// Applies filters (first to last) to every color sent to the wrapped device.
+pub struct FilteredDevice {
+    device: Box<Device>,
+    filters: Vec<Box<ColorFilter>>,
+}
+
+impl FilteredDevice {
+    pub fn new(device: Box<Device>, filters: Vec<Box<ColorFilter>>) -> FilteredDevice {
+        FilteredDevice { device, filters }
+    }
+
+    fn apply(&self, color: Color) -> Color {
+        self.filters.iter().fold(color, |color, filter| filter.apply(color))
+    }
+}
+
+impl Device for FilteredDevice {
+    fn name(&self) -> &'static str {
+        self.device.name()
+    }
+
+    fn hid_device<'a>(&'a self) -> &'a HidDevice {
+        self.device.hid_device()
+    }
+
+    fn brightness_scale(&self) -> BrightnessScale {
+        self.device.brightness_scale()
+    }
+
+    fn get_brightness(&self) -> Result<Brightness> {
+        self.device.get_brightness()
+    }
+
+    fn set_brightness(&self, brightness: Brightness, persistence: Persistence) -> Result<()> {
+        self.device.set_brightness(brightness, persistence)
+    }
+
+    fn get_zone_brightness(&self, zone: LedZone) -> Result<Brightness> {
+        self.device.get_zone_brightness(zone)
+    }
+
+    fn set_zone_brightness(&self, zone: LedZone, brightness: Brightness, persistence: Persistence) -> Result<()> {
+        self.device.set_zone_brightness(zone, brightness, persistence)
+    }
+
+    fn set_color(&self, color: Color, persistence: Persistence) -> Result<()> {
+        self.device.set_color(self.apply(color), persistence)
+    }
+
+    fn geometry(&self) -> Option<Geometry> {
+        self.device.geometry()
+    }
+
+    fn keymap(&self) -> Option<&'static Keymap> {
+        self.device.keymap()
+    }
+
+    fn set_frame(&self, frame: &Frame, persistence: Persistence) -> Result<()> {
+        self.device.set_frame(&frame.map(|color| self.apply(color)), persistence)
+    }
+}
@@ -0,0 +1,87 @@
+use super::razer_report::Color;
+use errors::{ErrorKind, Result};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Geometry {
+    pub rows: usize,
+    pub cols: usize,
+}
+
+impl Geometry {
+    pub fn new(rows: usize, cols: usize) -> Geometry {
+        Geometry { rows, cols }
+    }
+
+    pub fn len(&self) -> usize {
+        self.rows * self.cols
+    }
+}
+
+// Rows by columns, e.g. "6x22".
+impl FromStr for Geometry {
+    type Err = ::errors::Error;
+
+    fn from_str(s: &str) -> Result<Geometry> {
+        let invalid = || ErrorKind::InvalidParameter("geometry".to_string(), s.to_string());
+        let mut parts = s.splitn(2, 'x').map(|part| part.trim().parse::<usize>());
+
+        match (parts.next(), parts.next()) {
+            (Some(Ok(rows)), Some(Ok(cols))) if rows > 0 && cols > 0 => Ok(Geometry::new(rows, cols)),
+            _ => Err(invalid().into()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frame {
+    geometry: Geometry,
+    colors: Vec<Color>,
+}
+
+impl Frame {
+    pub fn new(geometry: Geometry) -> Frame {
+        Frame {
+            geometry,
+            colors: vec![Color::default(); geometry.len()],
+        }
+    }
+
+    pub fn geometry(&self) -> Geometry {
+        self.geometry
+    }
+
+    pub fn rows(&self) -> usize {
+        self.geometry.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.geometry.cols
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Color {
+        self.colors[row * self.geometry.cols + col]
+    }
+
+    pub fn set(&mut self, row: usize, col: usize, color: Color) {
+        self.colors[row * self.geometry.cols + col] = color;
+    }
+
+    pub fn fill(&mut self, color: Color) {
+        for c in self.colors.iter_mut() {
+            *c = color;
+        }
+    }
+
+    pub fn row(&self, row: usize) -> &[Color] {
+        let start = row * self.geometry.cols;
+        &self.colors[start..start + self.geometry.cols]
+    }
+
+    pub fn map<F: Fn(Color) -> Color>(&self, f: F) -> Frame {
+        Frame {
+            geometry: self.geometry,
+            colors: self.colors.iter().map(|color| f(*color)).collect(),
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use super::color::Interpolation;
+use super::frame::{Frame, Geometry};
+use super::razer_report::Color;
+use errors::{ErrorKind, Result};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Direction {
+    // Left to right.
+    Horizontal,
+    // Top to bottom.
+    Vertical,
+    // Center to the corners.
+    Radial,
+    // Degrees clockwise from left to right, e.g. 90 is top to bottom.
+    Angle(f64),
+}
+
+impl FromStr for Direction {
+    type Err = ::errors::Error;
+
+    fn from_str(s: &str) -> Result<Direction> {
+        match s {
+            "horizontal" => Ok(Direction::Horizontal),
+            "vertical" => Ok(Direction::Vertical),
+            "radial" => Ok(Direction::Radial),
+            _ => match s.trim_end_matches("deg").parse::<f64>() {
+                Ok(angle) if angle.is_finite() => Ok(Direction::Angle(angle)),
+                _ => Err(ErrorKind::InvalidParameter("direction".to_string(), s.to_string()).into()),
+            },
+        }
+    }
+}
+
+// Evenly spaced color stops laid out over a whole frame.
+#[derive(Debug, Clone)]
+pub struct Gradient {
+    pub stops: Vec<Color>,
+    pub direction: Direction,
+    pub interpolation: Interpolation,
+}
+
+impl Gradient {
+    pub fn new(stops: Vec<Color>, direction: Direction, interpolation: Interpolation) -> Gradient {
+        Gradient {
+            stops,
+            direction,
+            interpolation,
+        }
+    }
+
+    pub fn render(&self, geometry: Geometry) -> Frame {
+        let mut frame = Frame::new(geometry);
+        // Positions are cell centers, a single row or column gets the middle of the gradient.
+        let (width, height) = (geometry.cols as f64, geometry.rows as f64);
+
+        for row in 0..geometry.rows {
+            for col in 0..geometry.cols {
+                let (x, y) = (col as f64 + 0.5, row as f64 + 0.5);
+                let t = match self.direction {
+                    Direction::Horizontal => position(x, 0.5, width - 0.5),
+                    Direction::Vertical => position(y, 0.5, height - 0.5),
+                    Direction::Radial => {
+                        let (dx, dy) = (x - width / 2.0, y - height / 2.0);
+                        let corner = ((width - 1.0).powi(2) + (height - 1.0).powi(2)).sqrt() / 2.0;
+
+                        position((dx * dx + dy * dy).sqrt(), 0.0, corner)
+                    }
+                    Direction::Angle(angle) => {
+                        let (sin, cos) = angle.to_radians().sin_cos();
+                        let project = |x: f64, y: f64| x * cos + y * sin;
+                        let corners = [
+                            project(0.5, 0.5),
+                            project(width - 0.5, 0.5),
+                            project(0.5, height - 0.5),
+                            project(width - 0.5, height - 0.5),
+                        ];
+                        let min = corners.iter().cloned().fold(f64::INFINITY, f64::min);
+                        let max = corners.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+
+                        position(project(x, y), min, max)
+                    }
+                };
+
+                frame.set(row, col, Color::gradient(&self.stops, t, self.interpolation));
+            }
+        }
+
+        frame
+    }
+}
+
+// Where `value` is between `start` and `end`, 0.5 if they are the same.
+fn position(value: f64, start: f64, end: f64) -> f64 {
+    if end - start < 1e-9 {
+        0.5
+    } else {
+        (value - start) / (end - start)
+    }
+}
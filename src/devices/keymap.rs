@@ -0,0 +1,169 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Key {
+    // Linux evdev key code, 0 for LEDs that are not backed by a key (e.g. the logo)
+    pub code: u16,
+    pub name: &'static str,
+    pub row: usize,
+    pub col: usize,
+}
+
+#[derive(Debug)]
+pub struct Keymap {
+    keys: Vec<Key>,
+}
+
+impl Keymap {
+    fn new(layouts: &[&[(u16, &'static str, usize, usize)]]) -> Keymap {
+        Keymap {
+            keys: layouts
+                .iter()
+                .flat_map(|layout| layout.iter())
+                .map(|&(code, name, row, col)| Key { code, name, row, col })
+                .collect(),
+        }
+    }
+
+    pub fn keys(&self) -> &[Key] {
+        &self.keys
+    }
+
+    pub fn by_name(&self, name: &str) -> Option<&Key> {
+        self.keys.iter().find(|key| key.name == name)
+    }
+
+    pub fn by_code(&self, code: u16) -> Option<&Key> {
+        if code == 0 {
+            return None;
+        }
+        self.keys.iter().find(|key| key.code == code)
+    }
+}
+
+// US layout of a full size keyboard with the escape key in column 1.
+static ANSI_FULL_SIZE: &[(u16, &str, usize, usize)] = &[
+    (1, "esc", 0, 1),
+    (59, "f1", 0, 3),
+    (60, "f2", 0, 4),
+    (61, "f3", 0, 5),
+    (62, "f4", 0, 6),
+    (63, "f5", 0, 7),
+    (64, "f6", 0, 8),
+    (65, "f7", 0, 9),
+    (66, "f8", 0, 10),
+    (67, "f9", 0, 11),
+    (68, "f10", 0, 12),
+    (87, "f11", 0, 13),
+    (88, "f12", 0, 14),
+    (99, "print", 0, 15),
+    (70, "scrolllock", 0, 16),
+    (119, "pause", 0, 17),
+    (41, "grave", 1, 1),
+    (2, "1", 1, 2),
+    (3, "2", 1, 3),
+    (4, "3", 1, 4),
+    (5, "4", 1, 5),
+    (6, "5", 1, 6),
+    (7, "6", 1, 7),
+    (8, "7", 1, 8),
+    (9, "8", 1, 9),
+    (10, "9", 1, 10),
+    (11, "0", 1, 11),
+    (12, "minus", 1, 12),
+    (13, "equal", 1, 13),
+    (14, "backspace", 1, 14),
+    (110, "insert", 1, 15),
+    (102, "home", 1, 16),
+    (104, "pageup", 1, 17),
+    (69, "numlock", 1, 18),
+    (98, "kpslash", 1, 19),
+    (55, "kpasterisk", 1, 20),
+    (74, "kpminus", 1, 21),
+    (15, "tab", 2, 1),
+    (16, "q", 2, 2),
+    (17, "w", 2, 3),
+    (18, "e", 2, 4),
+    (19, "r", 2, 5),
+    (20, "t", 2, 6),
+    (21, "y", 2, 7),
+    (22, "u", 2, 8),
+    (23, "i", 2, 9),
+    (24, "o", 2, 10),
+    (25, "p", 2, 11),
+    (26, "leftbrace", 2, 12),
+    (27, "rightbrace", 2, 13),
+    (43, "backslash", 2, 14),
+    (111, "delete", 2, 15),
+    (107, "end", 2, 16),
+    (109, "pagedown", 2, 17),
+    (71, "kp7", 2, 18),
+    (72, "kp8", 2, 19),
+    (73, "kp9", 2, 20),
+    (78, "kpplus", 2, 21),
+    (58, "capslock", 3, 1),
+    (30, "a", 3, 2),
+    (31, "s", 3, 3),
+    (32, "d", 3, 4),
+    (33, "f", 3, 5),
+    (34, "g", 3, 6),
+    (35, "h", 3, 7),
+    (36, "j", 3, 8),
+    (37, "k", 3, 9),
+    (38, "l", 3, 10),
+    (39, "semicolon", 3, 11),
+    (40, "apostrophe", 3, 12),
+    (28, "enter", 3, 14),
+    (75, "kp4", 3, 18),
+    (76, "kp5", 3, 19),
+    (77, "kp6", 3, 20),
+    (42, "leftshift", 4, 1),
+    (44, "z", 4, 3),
+    (45, "x", 4, 4),
+    (46, "c", 4, 5),
+    (47, "v", 4, 6),
+    (48, "b", 4, 7),
+    (49, "n", 4, 8),
+    (50, "m", 4, 9),
+    (51, "comma", 4, 10),
+    (52, "dot", 4, 11),
+    (53, "slash", 4, 12),
+    (54, "rightshift", 4, 14),
+    (103, "up", 4, 16),
+    (79, "kp1", 4, 18),
+    (80, "kp2", 4, 19),
+    (81, "kp3", 4, 20),
+    (96, "kpenter", 4, 21),
+    (29, "leftctrl", 5, 1),
+    (125, "leftmeta", 5, 2),
+    (56, "leftalt", 5, 3),
+    (57, "space", 5, 7),
+    (100, "rightalt", 5, 11),
+    (464, "fn", 5, 12),
+    (127, "compose", 5, 13),
+    (97, "rightctrl", 5, 14),
+    (105, "left", 5, 15),
+    (108, "down", 5, 16),
+    (106, "right", 5, 17),
+    (82, "kp0", 5, 19),
+    (83, "kpdot", 5, 20),
+];
+
+static BLACKWIDOW_EXTRAS: &[(u16, &str, usize, usize)] = &[
+    (0, "logo", 0, 20),
+    (656, "m1", 1, 0),
+    (657, "m2", 2, 0),
+    (658, "m3", 3, 0),
+    (659, "m4", 4, 0),
+    (660, "m5", 5, 0),
+];
+
+static HUNTSMAN_ELITE_EXTRAS: &[(u16, &str, usize, usize)] = &[
+    (165, "previoussong", 0, 18),
+    (164, "playpause", 0, 19),
+    (163, "nextsong", 0, 20),
+    (113, "mute", 0, 21),
+];
+
+lazy_static! {
+    pub static ref BLACKWIDOW_KEYMAP: Keymap = Keymap::new(&[ANSI_FULL_SIZE, BLACKWIDOW_EXTRAS]);
+    pub static ref HUNTSMAN_ELITE_KEYMAP: Keymap = Keymap::new(&[ANSI_FULL_SIZE, HUNTSMAN_ELITE_EXTRAS]);
+}
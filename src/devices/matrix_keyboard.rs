@@ -1,17 +1,33 @@
+use super::brightness::{Brightness, BrightnessScale, LedZone};
+use super::frame::{Frame, Geometry};
+use super::keymap::Keymap;
+use super::persistence::Persistence;
 use super::razer_report::{Color, RazerMatrixEffectId, RazerReport, RazerVarstore};
 use super::{Device, DeviceFactory};
-use errors::Result;
+use errors::{ErrorKind, Result};
 use hidapi::HidDevice;
 
 #[derive(Clone, Debug)]
 pub struct MatrixKeyboardFactory {
     name: &'static str,
     led_ids: &'static [u8],
+    geometry: Geometry,
+    keymap: &'static Keymap,
 }
 
 impl MatrixKeyboardFactory {
-    pub fn new(name: &'static str, led_ids: &'static [u8]) -> Box<MatrixKeyboardFactory> {
-        Box::new(MatrixKeyboardFactory { name, led_ids })
+    pub fn new(
+        name: &'static str,
+        led_ids: &'static [u8],
+        geometry: Geometry,
+        keymap: &'static Keymap,
+    ) -> Box<MatrixKeyboardFactory> {
+        Box::new(MatrixKeyboardFactory {
+            name,
+            led_ids,
+            geometry,
+            keymap,
+        })
     }
 }
 
@@ -20,10 +36,20 @@ impl DeviceFactory for MatrixKeyboardFactory {
         self.name
     }
 
+    fn geometry(&self) -> Option<Geometry> {
+        Some(self.geometry)
+    }
+
+    fn keymap(&self) -> Option<&'static Keymap> {
+        Some(self.keymap)
+    }
+
     fn open(&self, hid_device: HidDevice) -> Box<Device> {
         Box::new(MatrixKeyboard {
             name: self.name,
             led_ids: self.led_ids,
+            geometry: self.geometry,
+            keymap: self.keymap,
             hid_device,
         })
     }
@@ -32,9 +58,27 @@ impl DeviceFactory for MatrixKeyboardFactory {
 pub struct MatrixKeyboard {
     name: &'static str,
     led_ids: &'static [u8],
+    geometry: Geometry,
+    keymap: &'static Keymap,
     hid_device: HidDevice,
 }
 
+impl MatrixKeyboard {
+    fn get_led_brightness(&self, led_id: u8) -> Result<Brightness> {
+        let result = self.send_report(RazerReport::standard_get_led_brightness(RazerVarstore::NoStore, led_id))?;
+        Ok(Brightness::from_raw(result.arguments[2], self.brightness_scale()))
+    }
+
+    fn set_led_brightness(&self, led_id: u8, brightness: Brightness, persistence: Persistence) -> Result<()> {
+        self.send_report(RazerReport::standard_set_led_brightness(
+            persistence.varstore(),
+            led_id,
+            brightness.to_raw(self.brightness_scale()),
+        ))?;
+        Ok(())
+    }
+}
+
 impl Device for MatrixKeyboard {
     fn name(&self) -> &'static str {
         self.name
@@ -44,26 +88,42 @@ impl Device for MatrixKeyboard {
         &self.hid_device
     }
 
-    fn get_brightness(&self) -> Result<u8> {
-        self.send_report(RazerReport::standard_get_led_brightness(
-            RazerVarstore::Store,
-            self.led_ids[0],
-        ))?;
-        Ok(0)
+    fn brightness_scale(&self) -> BrightnessScale {
+        BrightnessScale::Standard
     }
 
-    fn set_brightness(&self, brightness: u8) -> Result<()> {
+    fn get_brightness(&self) -> Result<Brightness> {
+        self.get_led_brightness(self.led_ids[0])
+    }
+
+    fn set_brightness(&self, brightness: Brightness, persistence: Persistence) -> Result<()> {
         for led_id in self.led_ids {
-            self.send_report(RazerReport::standard_set_led_brightness(
-                RazerVarstore::Store,
-                *led_id,
-                brightness,
-            ))?;
+            self.set_led_brightness(*led_id, brightness, persistence)?;
         }
         Ok(())
     }
 
-    fn set_color(&self, color: Color) -> Result<()> {
+    fn get_zone_brightness(&self, zone: LedZone) -> Result<Brightness> {
+        if !self.led_ids.contains(&zone.led_id()) {
+            return Err(ErrorKind::NotSupported.into());
+        }
+        self.get_led_brightness(zone.led_id())
+    }
+
+    fn set_zone_brightness(&self, zone: LedZone, brightness: Brightness, persistence: Persistence) -> Result<()> {
+        if !self.led_ids.contains(&zone.led_id()) {
+            return Err(ErrorKind::NotSupported.into());
+        }
+        self.set_led_brightness(zone.led_id(), brightness, persistence)
+    }
+
+    fn set_color(&self, color: Color, persistence: Persistence) -> Result<()> {
+        // The standard static effect takes no varstore, the custom frame effect does.
+        if persistence == Persistence::Stored {
+            let mut frame = Frame::new(self.geometry);
+            frame.fill(color);
+            return self.set_frame(&frame, persistence);
+        }
         let mut report = RazerReport::standard_matrix_effect(RazerMatrixEffectId::Static);
         report.arguments[1] = color.red;
         report.arguments[2] = color.green;
@@ -72,4 +132,27 @@ impl Device for MatrixKeyboard {
         self.send_report(report)?;
         Ok(())
     }
+
+    fn geometry(&self) -> Option<Geometry> {
+        Some(self.geometry)
+    }
+
+    fn keymap(&self) -> Option<&'static Keymap> {
+        Some(self.keymap)
+    }
+
+    fn set_frame(&self, frame: &Frame, persistence: Persistence) -> Result<()> {
+        if frame.geometry() != self.geometry {
+            return Err(ErrorKind::GeometryMismatch.into());
+        }
+        for row in 0..frame.rows() {
+            self.send_report(RazerReport::standard_matrix_frame(row as u8, 0, frame.row(row)))?;
+        }
+
+        let mut report = RazerReport::standard_matrix_effect(RazerMatrixEffectId::CustomFrame);
+        report.arguments[1] = persistence.varstore() as u8;
+
+        self.send_report(report)?;
+        Ok(())
+    }
 }
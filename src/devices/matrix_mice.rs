@@ -1,17 +1,25 @@
+use super::brightness::{Brightness, LedZone};
+use super::frame::{Frame, Geometry};
+use super::persistence::Persistence;
 use super::razer_report::{Color, RazerMouseMatrixEffectId, RazerReport, RazerVarstore};
 use super::{Device, DeviceFactory};
-use errors::Result;
+use errors::{ErrorKind, Result};
 use hidapi::HidDevice;
 
 #[derive(Clone, Debug)]
 pub struct MatrixMiceFactory {
     name: &'static str,
     led_ids: &'static [u8],
+    geometry: Geometry,
 }
 
 impl MatrixMiceFactory {
-    pub fn new(name: &'static str, led_ids: &'static [u8]) -> Box<MatrixMiceFactory> {
-        Box::new(MatrixMiceFactory { name, led_ids })
+    pub fn new(name: &'static str, led_ids: &'static [u8], geometry: Geometry) -> Box<MatrixMiceFactory> {
+        Box::new(MatrixMiceFactory {
+            name,
+            led_ids,
+            geometry,
+        })
     }
 }
 
@@ -20,10 +28,15 @@ impl DeviceFactory for MatrixMiceFactory {
         self.name
     }
 
+    fn geometry(&self) -> Option<Geometry> {
+        Some(self.geometry)
+    }
+
     fn open(&self, hid_device: HidDevice) -> Box<Device> {
         Box::new(MatrixMice {
             name: self.name,
             led_ids: self.led_ids,
+            geometry: self.geometry,
             hid_device,
         })
     }
@@ -32,9 +45,26 @@ impl DeviceFactory for MatrixMiceFactory {
 pub struct MatrixMice {
     name: &'static str,
     led_ids: &'static [u8],
+    geometry: Geometry,
     hid_device: HidDevice,
 }
 
+impl MatrixMice {
+    fn get_led_brightness(&self, led_id: u8) -> Result<Brightness> {
+        let result = self.send_report(RazerReport::extended_matrix_get_brightness(RazerVarstore::NoStore, led_id))?;
+        Ok(Brightness::from_raw(result.arguments[2], self.brightness_scale()))
+    }
+
+    fn set_led_brightness(&self, led_id: u8, brightness: Brightness, persistence: Persistence) -> Result<()> {
+        self.send_report(RazerReport::extended_matrix_set_brightness(
+            persistence.varstore(),
+            led_id,
+            brightness.to_raw(self.brightness_scale()),
+        ))?;
+        Ok(())
+    }
+}
+
 impl Device for MatrixMice {
     fn name(&self) -> &'static str {
         self.name
@@ -44,29 +74,35 @@ impl Device for MatrixMice {
         &self.hid_device
     }
 
-    fn get_brightness(&self) -> Result<u8> {
-        self.send_report(RazerReport::extended_matrix_get_brightness(
-            RazerVarstore::Store,
-            self.led_ids[0],
-        ))?;
-        Ok(0)
+    fn get_brightness(&self) -> Result<Brightness> {
+        self.get_led_brightness(self.led_ids[0])
     }
 
-    fn set_brightness(&self, brightness: u8) -> Result<()> {
+    fn set_brightness(&self, brightness: Brightness, persistence: Persistence) -> Result<()> {
         for led_id in self.led_ids {
-            self.send_report(RazerReport::extended_matrix_set_brightness(
-                RazerVarstore::Store,
-                *led_id,
-                brightness,
-            ))?;
+            self.set_led_brightness(*led_id, brightness, persistence)?;
         }
         Ok(())
     }
 
-    fn set_color(&self, color: Color) -> Result<()> {
+    fn get_zone_brightness(&self, zone: LedZone) -> Result<Brightness> {
+        if !self.led_ids.contains(&zone.led_id()) {
+            return Err(ErrorKind::NotSupported.into());
+        }
+        self.get_led_brightness(zone.led_id())
+    }
+
+    fn set_zone_brightness(&self, zone: LedZone, brightness: Brightness, persistence: Persistence) -> Result<()> {
+        if !self.led_ids.contains(&zone.led_id()) {
+            return Err(ErrorKind::NotSupported.into());
+        }
+        self.set_led_brightness(zone.led_id(), brightness, persistence)
+    }
+
+    fn set_color(&self, color: Color, persistence: Persistence) -> Result<()> {
         for led_id in self.led_ids {
             let mut report = RazerReport::extended_mouse_matrix_effect(
-                RazerVarstore::Store,
+                persistence.varstore(),
                 *led_id,
                 RazerMouseMatrixEffectId::Static,
             );
@@ -79,4 +115,24 @@ impl Device for MatrixMice {
         }
         Ok(())
     }
+
+    fn geometry(&self) -> Option<Geometry> {
+        Some(self.geometry)
+    }
+
+    fn set_frame(&self, frame: &Frame, persistence: Persistence) -> Result<()> {
+        if frame.geometry() != self.geometry {
+            return Err(ErrorKind::GeometryMismatch.into());
+        }
+        for row in 0..frame.rows() {
+            self.send_report(RazerReport::soft_matrix_frame(row as u8, 0, frame.row(row)))?;
+        }
+
+        self.send_report(RazerReport::extended_mouse_matrix_effect(
+            persistence.varstore(),
+            0,
+            RazerMouseMatrixEffectId::CustomFrame,
+        ))?;
+        Ok(())
+    }
 }
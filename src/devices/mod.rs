@@ -1,18 +1,43 @@
 use std::collections::HashMap;
 
+mod brightness;
+mod calibration;
+mod color;
+mod filter;
+mod frame;
+mod gradient;
+mod keymap;
 mod matrix_keyboard;
 mod matrix_mice;
+mod persistence;
 mod razer_report;
+mod resample;
 mod soft_keyboard;
+mod vision;
+mod zone;
 
+pub use self::brightness::{Brightness, BrightnessScale, LedZone};
+pub use self::calibration::{Calibration, CalibrationProfiles};
+pub use self::color::{split_colors, Interpolation};
+pub use self::filter::{ColorFilter, FilteredDevice};
+pub use self::frame::{Frame, Geometry};
+pub use self::gradient::{Direction, Gradient};
+pub use self::keymap::Keymap;
+pub use self::persistence::Persistence;
 pub use self::razer_report::Color;
+pub use self::resample::{sample_bilinear, Sampling};
+pub use self::vision::VisionProfile;
+pub use self::zone::Zone;
 use errors::{Error, ErrorKind, Result};
 use hidapi::{HidApi, HidDevice};
 use log::Level;
+use std::env;
 use std::ffi::CString;
+use std::path::PathBuf;
 use std::thread;
 use std::time;
 
+use self::keymap::{BLACKWIDOW_KEYMAP, HUNTSMAN_ELITE_KEYMAP};
 use self::matrix_keyboard::MatrixKeyboardFactory;
 use self::matrix_mice::MatrixMiceFactory;
 use self::razer_report::{RazerReport, RazerStatus};
@@ -30,6 +55,14 @@ pub struct DeviceId {
 pub trait DeviceFactory: Sync {
     fn name(&self) -> &'static str;
 
+    fn geometry(&self) -> Option<Geometry> {
+        None
+    }
+
+    fn keymap(&self) -> Option<&'static Keymap> {
+        None
+    }
+
     fn open(&self, hid_device: HidDevice) -> Box<Device>;
 }
 
@@ -38,11 +71,51 @@ pub trait Device {
 
     fn hid_device<'a>(&'a self) -> &'a HidDevice;
 
-    fn get_brightness(&self) -> Result<u8>;
+    // The raw levels taken by the brightness reports the device uses.
+    fn brightness_scale(&self) -> BrightnessScale {
+        BrightnessScale::Extended
+    }
+
+    fn get_brightness(&self) -> Result<Brightness>;
 
-    fn set_brightness(&self, brightness: u8) -> Result<()>;
+    fn set_brightness(&self, brightness: Brightness, persistence: Persistence) -> Result<()>;
 
-    fn set_color(&self, color: Color) -> Result<()>;
+    fn get_zone_brightness(&self, _zone: LedZone) -> Result<Brightness> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    fn set_zone_brightness(&self, _zone: LedZone, _brightness: Brightness, _persistence: Persistence) -> Result<()> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    fn set_color(&self, color: Color, persistence: Persistence) -> Result<()>;
+
+    fn geometry(&self) -> Option<Geometry> {
+        None
+    }
+
+    fn keymap(&self) -> Option<&'static Keymap> {
+        None
+    }
+
+    fn set_frame(&self, _frame: &Frame, _persistence: Persistence) -> Result<()> {
+        Err(ErrorKind::NotSupported.into())
+    }
+
+    fn set_gradient(&self, gradient: &Gradient, persistence: Persistence) -> Result<()> {
+        match self.geometry() {
+            Some(geometry) => self.set_frame(&gradient.render(geometry), persistence),
+            None => Err(ErrorKind::NotSupported.into()),
+        }
+    }
+
+    fn layout(&self) -> Option<Layout> {
+        self.geometry().map(|geometry| Layout {
+            name: self.name(),
+            geometry,
+            keymap: self.keymap(),
+        })
+    }
 
     fn get_manufacturer(&self) -> Result<Option<String>> {
         Ok(self.hid_device().get_manufacturer_string()?)
@@ -112,6 +185,15 @@ pub trait Device {
     }
 }
 
+// What an effect needs to know about a device with custom frames, also
+// available for devices that are not connected (e.g. for previews).
+#[derive(Debug, Clone, Copy)]
+pub struct Layout {
+    pub name: &'static str,
+    pub geometry: Geometry,
+    pub keymap: Option<&'static Keymap>,
+}
+
 impl DeviceId {
     pub fn new(vendor_id: u16, product_id: u16, interface_number: i32) -> DeviceId {
         DeviceId {
@@ -128,21 +210,65 @@ lazy_static! {
 
         map.insert(
             DeviceId::new(RAZER_VENDOR, 0x0060, 0),
-            MatrixMiceFactory::new("Razer Lancehead TE", &[1, 4, 16, 17]),
+            MatrixMiceFactory::new("Razer Lancehead TE", &[1, 4, 16, 17], Geometry::new(1, 16)),
         );
         map.insert(
             DeviceId::new(RAZER_VENDOR, 0x0226, 0),
-            SoftKeyboardFactory::new("Razer Huntsman Elite"),
+            SoftKeyboardFactory::new("Razer Huntsman Elite", Geometry::new(9, 23), &HUNTSMAN_ELITE_KEYMAP),
         );
         map.insert(
             DeviceId::new(RAZER_VENDOR, 0x0221, 0),
-            MatrixKeyboardFactory::new("Razer BlackWidow Chroma V2", &[5]),
+            MatrixKeyboardFactory::new(
+                "Razer BlackWidow Chroma V2",
+                &[5],
+                Geometry::new(6, 22),
+                &BLACKWIDOW_KEYMAP,
+            ),
         );
         map
     };
 }
 
+// Connected devices, with the user's vision profile and their calibration
+// profile applied (in that order). A broken profile file is left out rather
+// than making every command fail.
 pub fn list_devices() -> Result<Vec<Box<Device>>> {
+    let vision = VisionProfile::load_default().unwrap_or_else(|error| {
+        warn!("Ignoring the vision profile: {}", error);
+        None
+    });
+    let profiles = CalibrationProfiles::load_default().unwrap_or_else(|error| {
+        warn!("Ignoring the calibration profiles: {}", error);
+        CalibrationProfiles::default()
+    });
+    let mut devices: Vec<Box<Device>> = Vec::new();
+
+    for device in list_unfiltered_devices()? {
+        let mut filters: Vec<Box<ColorFilter>> = Vec::new();
+        let serial = if profiles.has_serials() {
+            device.get_serial().ok().map(|serial| serial.to_string_lossy().into_owned())
+        } else {
+            None
+        };
+
+        if let Some(ref vision) = vision {
+            filters.push(Box::new(vision.clone()));
+        }
+        if let Some(calibration) = profiles.find(device.name(), serial.as_deref()) {
+            filters.push(Box::new(calibration));
+        }
+
+        if filters.is_empty() {
+            devices.push(device);
+        } else {
+            devices.push(Box::new(FilteredDevice::new(device, filters)));
+        }
+    }
+
+    Ok(devices)
+}
+
+pub fn list_unfiltered_devices() -> Result<Vec<Box<Device>>> {
     let api = HidApi::new()?;
     let mut devices: Vec<Box<Device>> = Vec::new();
 
@@ -160,3 +286,45 @@ pub fn list_devices() -> Result<Vec<Box<Device>>> {
 
     Ok(devices)
 }
+
+pub fn known_layouts() -> Vec<Layout> {
+    let mut layouts: Vec<Layout> = known_devices
+        .values()
+        .filter_map(|factory| {
+            factory.geometry().map(|geometry| Layout {
+                name: factory.name(),
+                geometry,
+                keymap: factory.keymap(),
+            })
+        }).collect();
+
+    layouts.sort_by_key(|layout| layout.name);
+    layouts.dedup_by_key(|layout| layout.name);
+    layouts
+}
+
+// Layouts whose name contains `pattern` (case-insensitive), or all of them for "all".
+pub fn find_layouts(pattern: &str) -> Result<Vec<Layout>> {
+    let lowercase = pattern.to_lowercase();
+    let layouts: Vec<Layout> = known_layouts()
+        .into_iter()
+        .filter(|layout| lowercase == "all" || layout.name.to_lowercase().contains(&lowercase))
+        .collect();
+
+    if layouts.is_empty() {
+        return Err(ErrorKind::UnknownDevice(pattern.to_string()).into());
+    }
+    Ok(layouts)
+}
+
+// `name` in the user's config directory, unless `variable` points elsewhere.
+pub fn config_path(variable: &str, name: &str) -> Option<PathBuf> {
+    if let Some(path) = env::var_os(variable) {
+        return Some(PathBuf::from(path));
+    }
+    let config = match env::var_os("XDG_CONFIG_HOME") {
+        Some(config) => PathBuf::from(config),
+        None => PathBuf::from(env::var_os("HOME")?).join(".config"),
+    };
+    Some(config.join("razer_test_test").join(name))
+}
@@ -0,0 +1,20 @@
+use super::razer_report::RazerVarstore;
+
+// Whether a write survives the device being unplugged. Stored writes go to
+// the device's non-volatile memory, so animations and experiments should
+// stay transient.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Persistence {
+    #[default]
+    Transient,
+    Stored,
+}
+
+impl Persistence {
+    pub fn varstore(self) -> RazerVarstore {
+        match self {
+            Persistence::Transient => RazerVarstore::NoStore,
+            Persistence::Stored => RazerVarstore::Store,
+        }
+    }
+}
@@ -4,8 +4,6 @@ use std::mem;
 use std::ptr;
 use std::slice;
 
-use errors::{ErrorKind, Result};
-
 #[allow(dead_code)]
 pub enum RazerStatus {
     New = 0x00,
@@ -16,7 +14,6 @@ pub enum RazerStatus {
     NotSupported = 0x05,
 }
 
-#[allow(dead_code)]
 pub enum RazerVarstore {
     NoStore = 0x00,
     Store = 0x01,
@@ -47,7 +44,7 @@ pub enum RazerMatrixEffectId {
 }
 
 
-#[derive(Default, Clone, Copy)]
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
 #[repr(packed)]
 pub struct Color {
     pub red: u8,
@@ -60,20 +57,6 @@ impl Color {
     pub fn new(red: u8, green: u8, blue: u8) -> Color {
         Color { red, green, blue }
     }
-
-    pub fn parse(color_str: &str) -> Result<Color> {
-        let parts: Vec<u8> = color_str.split(',').map(|p| p.parse::<u8>().unwrap_or(0)).collect();
-
-        if parts.len() != 3 {
-            return Err(ErrorKind::InvalidColorFormat.into());
-        }
-
-        Ok(Color {
-            red: parts[0],
-            green: parts[1],
-            blue: parts[2],
-        })
-    }
 }
 
 #[repr(packed)]
@@ -145,6 +128,29 @@ impl RazerReport {
         report
     }
 
+    pub fn standard_matrix_frame(row: u8, start_col: u8, colors: &[Color]) -> RazerReport {
+        let mut report = RazerReport {
+            transaction_id: 0x1f,
+            command_class: 0x3,
+            command_id: 0x0b,
+            data_size: 0x46,
+            ..Default::default()
+        };
+        report.arguments[0] = 0xff;
+        report.arguments[1] = row;
+        report.arguments[2] = start_col;
+        report.arguments[3] = start_col + (colors.len() as u8) - 1;
+        unsafe {
+            ptr::copy_nonoverlapping::<u8>(
+                (&colors[0] as *const Color) as *const u8,
+                &mut report.arguments[4],
+                colors.len() * 3,
+            )
+        }
+
+        report
+    }
+
     pub fn extended_matrix_get_brightness(store: RazerVarstore, led_id: u8) -> RazerReport {
         let mut report = RazerReport {
             transaction_id: 0x1f,
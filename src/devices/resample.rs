@@ -0,0 +1,198 @@
+use super::frame::{Frame, Geometry};
+use super::razer_report::Color;
+use errors::{ErrorKind, Result};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Sampling {
+    // The closest cell, keeps hard edges.
+    Nearest,
+    // Blends the four closest cells.
+    #[default]
+    Bilinear,
+    // Averages all cells covered, best when shrinking.
+    Area,
+}
+
+impl FromStr for Sampling {
+    type Err = ::errors::Error;
+
+    fn from_str(s: &str) -> Result<Sampling> {
+        match s {
+            "nearest" => Ok(Sampling::Nearest),
+            "bilinear" => Ok(Sampling::Bilinear),
+            "area" => Ok(Sampling::Area),
+            _ => Err(ErrorKind::InvalidParameter("sampling".to_string(), s.to_string()).into()),
+        }
+    }
+}
+
+impl Frame {
+    // The frame stretched over another geometry, e.g. to show an effect
+    // rendered for one keyboard on another one or on a mouse.
+    pub fn resample(&self, geometry: Geometry, sampling: Sampling) -> Frame {
+        if geometry == self.geometry() {
+            return self.clone();
+        }
+        let mut frame = Frame::new(geometry);
+        let scale_x = self.cols() as f64 / geometry.cols as f64;
+        let scale_y = self.rows() as f64 / geometry.rows as f64;
+
+        for row in 0..geometry.rows {
+            for col in 0..geometry.cols {
+                let color = match sampling {
+                    Sampling::Nearest => {
+                        let source_col = ((col as f64 + 0.5) * scale_x) as usize;
+                        let source_row = ((row as f64 + 0.5) * scale_y) as usize;
+
+                        self.get(source_row.min(self.rows() - 1), source_col.min(self.cols() - 1))
+                    }
+                    Sampling::Bilinear => sample_bilinear(
+                        self,
+                        (col as f64 + 0.5) * scale_x - 0.5,
+                        (row as f64 + 0.5) * scale_y - 0.5,
+                    ),
+                    Sampling::Area => self.average(
+                        (col as f64 * scale_x, (col + 1) as f64 * scale_x),
+                        (row as f64 * scale_y, (row + 1) as f64 * scale_y),
+                    ),
+                };
+
+                frame.set(row, col, color);
+            }
+        }
+
+        frame
+    }
+
+    // Mean of the cells inside of a rectangle, weighted by how much of them it covers.
+    fn average(&self, (left, right): (f64, f64), (top, bottom): (f64, f64)) -> Color {
+        let mut sum = [0.0; 3];
+        let mut total = 0.0;
+
+        for row in top.floor() as usize..(bottom.ceil() as usize).min(self.rows()) {
+            let height = bottom.min(row as f64 + 1.0) - top.max(row as f64);
+
+            for col in left.floor() as usize..(right.ceil() as usize).min(self.cols()) {
+                let weight = height * (right.min(col as f64 + 1.0) - left.max(col as f64));
+                let color = self.get(row, col);
+
+                sum[0] += color.red as f64 * weight;
+                sum[1] += color.green as f64 * weight;
+                sum[2] += color.blue as f64 * weight;
+                total += weight;
+            }
+        }
+        if total <= 0.0 {
+            return Color::default();
+        }
+        Color::from_f64(sum[0] / total / 255.0, sum[1] / total / 255.0, sum[2] / total / 255.0)
+    }
+}
+
+// Bilinear interpolation at a position in cells, clamped to the frame.
+pub fn sample_bilinear(frame: &Frame, x: f64, y: f64) -> Color {
+    let x = x.clamp(0.0, (frame.cols() - 1) as f64);
+    let y = y.clamp(0.0, (frame.rows() - 1) as f64);
+    let (col, row) = (x.floor() as usize, y.floor() as usize);
+    let (next_col, next_row) = ((col + 1).min(frame.cols() - 1), (row + 1).min(frame.rows() - 1));
+    let (fx, fy) = (x - col as f64, y - row as f64);
+    let top = frame.get(row, col).mix(frame.get(row, next_col), fx);
+    let bottom = frame.get(next_row, col).mix(frame.get(next_row, next_col), fx);
+
+    top.mix(bottom, fy)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: [Sampling; 3] = [Sampling::Nearest, Sampling::Bilinear, Sampling::Area];
+
+    // 9x23 is the Huntsman Elite, 6x22 the BlackWidow and 1x16 the Lancehead.
+    const CONVERSIONS: [((usize, usize), (usize, usize)); 4] =
+        [((9, 23), (6, 22)), ((6, 22), (1, 16)), ((1, 16), (6, 22)), ((6, 22), (9, 23))];
+
+    fn ramp(geometry: Geometry) -> Frame {
+        let mut frame = Frame::new(geometry);
+        for row in 0..geometry.rows {
+            for col in 0..geometry.cols {
+                let red = (col * 255 / (geometry.cols - 1)) as u8;
+                frame.set(row, col, Color::new(red, 0, 255 - red));
+            }
+        }
+        frame
+    }
+
+    fn strip(colors: &[Color]) -> Frame {
+        let mut frame = Frame::new(Geometry::new(1, colors.len()));
+        for (col, &color) in colors.iter().enumerate() {
+            frame.set(0, col, color);
+        }
+        frame
+    }
+
+    #[test]
+    fn keeps_solid_colors_exact() {
+        let color = Color::new(37, 201, 99);
+
+        for &((rows, cols), (to_rows, to_cols)) in &CONVERSIONS {
+            let mut frame = Frame::new(Geometry::new(rows, cols));
+            frame.fill(color);
+
+            for &sampling in &ALL {
+                let resampled = frame.resample(Geometry::new(to_rows, to_cols), sampling);
+                let mut expected = Frame::new(Geometry::new(to_rows, to_cols));
+                expected.fill(color);
+                assert_eq!(resampled, expected, "{:?} {:?}", sampling, (to_rows, to_cols));
+            }
+        }
+    }
+
+    #[test]
+    fn keeps_ramps_monotonic() {
+        for &((rows, cols), (to_rows, to_cols)) in &CONVERSIONS {
+            let frame = ramp(Geometry::new(rows, cols));
+
+            for &sampling in &ALL {
+                let resampled = frame.resample(Geometry::new(to_rows, to_cols), sampling);
+
+                for row in 0..to_rows {
+                    let reds: Vec<u8> = resampled.row(row).iter().map(|color| color.red).collect();
+                    assert!(reds.windows(2).all(|pair| pair[0] <= pair[1]), "{:?} {:?}", sampling, reds);
+                    assert!(reds[0] < reds[to_cols - 1]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn samples_each_way() {
+        let (black, white) = (Color::new(0, 0, 0), Color::new(255, 255, 255));
+        let frame = strip(&[black, white, black, white]);
+        let half = Geometry::new(1, 2);
+
+        assert_eq!(frame.resample(frame.geometry(), Sampling::Area), frame);
+        assert_eq!(frame.resample(half, Sampling::Nearest), strip(&[white, white]));
+        assert_eq!(frame.resample(half, Sampling::Area), strip(&[black.mix(white, 0.5); 2]));
+        // Cell centers land between two cells, halfway in the middle.
+        assert_eq!(frame.resample(half, Sampling::Bilinear), strip(&[black.mix(white, 0.5); 2]));
+    }
+
+    #[test]
+    fn interpolates_between_cells() {
+        let (black, white) = (Color::new(0, 0, 0), Color::new(255, 255, 255));
+        let mut frame = Frame::new(Geometry::new(2, 2));
+        frame.set(0, 1, white);
+        frame.set(1, 0, white);
+        frame.set(1, 1, white);
+
+        assert_eq!(sample_bilinear(&frame, 0.0, 0.0), black);
+        assert_eq!(sample_bilinear(&frame, 1.0, 0.0), white);
+        assert_eq!(sample_bilinear(&frame, 0.5, 0.0), Color::new(128, 128, 128));
+        assert_eq!(sample_bilinear(&frame, 0.0, 0.25), Color::new(64, 64, 64));
+        // Positions outside of the frame are clamped to the edge.
+        assert_eq!(sample_bilinear(&frame, -3.0, -3.0), black);
+        assert_eq!(sample_bilinear(&frame, 5.0, -1.0), white);
+    }
+}
@@ -1,15 +1,25 @@
-use super::razer_report::{Color, RazerReport, RazerVarstore};
+use super::brightness::{Brightness, LedZone};
+use super::frame::{Frame, Geometry};
+use super::keymap::Keymap;
+use super::persistence::Persistence;
+use super::razer_report::{Color, RazerMouseMatrixEffectId, RazerReport, RazerVarstore};
 use super::{Device, DeviceFactory};
-use errors::Result;
+use errors::{ErrorKind, Result};
 use hidapi::HidDevice;
 
 pub struct SoftKeyboardFactory {
     name: &'static str,
+    geometry: Geometry,
+    keymap: &'static Keymap,
 }
 
 impl SoftKeyboardFactory {
-    pub fn new(name: &'static str) -> Box<SoftKeyboardFactory> {
-        Box::new(SoftKeyboardFactory { name })
+    pub fn new(name: &'static str, geometry: Geometry, keymap: &'static Keymap) -> Box<SoftKeyboardFactory> {
+        Box::new(SoftKeyboardFactory {
+            name,
+            geometry,
+            keymap,
+        })
     }
 }
 
@@ -18,9 +28,19 @@ impl DeviceFactory for SoftKeyboardFactory {
         self.name.clone()
     }
 
+    fn geometry(&self) -> Option<Geometry> {
+        Some(self.geometry)
+    }
+
+    fn keymap(&self) -> Option<&'static Keymap> {
+        Some(self.keymap)
+    }
+
     fn open(&self, hid_device: HidDevice) -> Box<Device> {
         Box::new(SoftKeyboard {
             name: self.name,
+            geometry: self.geometry,
+            keymap: self.keymap,
             hid_device,
         })
     }
@@ -28,6 +48,8 @@ impl DeviceFactory for SoftKeyboardFactory {
 
 pub struct SoftKeyboard {
     name: &'static str,
+    geometry: Geometry,
+    keymap: &'static Keymap,
     hid_device: HidDevice,
 }
 
@@ -40,24 +62,50 @@ impl Device for SoftKeyboard {
         &self.hid_device
     }
 
-    fn get_brightness(&self) -> Result<u8> {
-        self.send_report(RazerReport::extended_matrix_get_brightness(RazerVarstore::Store, 0))?;
-        Ok(0)
+    fn get_brightness(&self) -> Result<Brightness> {
+        let result = self.send_report(RazerReport::extended_matrix_get_brightness(RazerVarstore::NoStore, 0))?;
+        Ok(Brightness::from_raw(result.arguments[2], self.brightness_scale()))
     }
 
-    fn set_brightness(&self, brightness: u8) -> Result<()> {
+    fn set_brightness(&self, brightness: Brightness, persistence: Persistence) -> Result<()> {
         self.send_report(RazerReport::extended_matrix_set_brightness(
-            RazerVarstore::Store,
+            persistence.varstore(),
             0,
-            brightness,
+            brightness.to_raw(self.brightness_scale()),
         ))?;
         Ok(())
     }
 
-    fn set_color(&self, color: Color) -> Result<()> {
-        let colors = [color; 23];
-        for row in 0..9 {
-            self.send_report(RazerReport::soft_matrix_frame(row, 0, &colors))?;
+    fn set_color(&self, color: Color, persistence: Persistence) -> Result<()> {
+        let mut frame = Frame::new(self.geometry);
+        frame.fill(color);
+        self.set_frame(&frame, persistence)
+    }
+
+    fn geometry(&self) -> Option<Geometry> {
+        Some(self.geometry)
+    }
+
+    fn keymap(&self) -> Option<&'static Keymap> {
+        Some(self.keymap)
+    }
+
+    fn set_frame(&self, frame: &Frame, persistence: Persistence) -> Result<()> {
+        if frame.geometry() != self.geometry {
+            return Err(ErrorKind::GeometryMismatch.into());
+        }
+        for row in 0..frame.rows() {
+            self.send_report(RazerReport::soft_matrix_frame(row as u8, 0, frame.row(row)))?;
+        }
+
+        // Soft frames are shown as they arrive, switching to the custom frame
+        // effect with the store varstore keeps the frame.
+        if persistence == Persistence::Stored {
+            self.send_report(RazerReport::extended_mouse_matrix_effect(
+                persistence.varstore(),
+                LedZone::Backlight.led_id(),
+                RazerMouseMatrixEffectId::CustomFrame,
+            ))?;
         }
         Ok(())
     }
@@ -0,0 +1,231 @@
+use super::config_path;
+use super::filter::ColorFilter;
+use super::razer_report::Color;
+use errors::{ErrorKind, Result, ResultExt};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use toml;
+
+#[derive(Debug, Deserialize)]
+struct VisionFile {
+    deficiency: Option<String>,
+    strength: Option<f64>,
+    #[serde(default)]
+    remap: Vec<RemapFile>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RemapFile {
+    from: String,
+    to: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Deficiency {
+    Protanopia,
+    Deuteranopia,
+    Tritanopia,
+}
+
+impl FromStr for Deficiency {
+    type Err = ::errors::Error;
+
+    fn from_str(s: &str) -> Result<Deficiency> {
+        match s {
+            "protanopia" => Ok(Deficiency::Protanopia),
+            "deuteranopia" => Ok(Deficiency::Deuteranopia),
+            "tritanopia" => Ok(Deficiency::Tritanopia),
+            _ => Err(ErrorKind::InvalidParameter("deficiency".to_string(), s.to_string()).into()),
+        }
+    }
+}
+
+impl Deficiency {
+    // How the color looks with the deficiency, in linear RGB (Machado et al. 2009).
+    fn simulation(self) -> [[f64; 3]; 3] {
+        match self {
+            Deficiency::Protanopia => [
+                [0.152_286, 1.052_583, -0.204_868],
+                [0.114_503, 0.786_281, 0.099_216],
+                [-0.003_882, -0.048_116, 1.051_998],
+            ],
+            Deficiency::Deuteranopia => [
+                [0.367_322, 0.860_646, -0.227_968],
+                [0.280_085, 0.672_501, 0.047_413],
+                [-0.011_820, 0.042_940, 0.968_881],
+            ],
+            Deficiency::Tritanopia => [
+                [1.255_528, -0.076_749, -0.178_779],
+                [-0.078_411, 0.930_809, 0.147_602],
+                [0.004_733, 0.691_367, 0.303_900],
+            ],
+        }
+    }
+
+    // Moves the information lost by the deficiency to channels that are still seen.
+    fn shift(self) -> [[f64; 3]; 3] {
+        match self {
+            Deficiency::Protanopia | Deficiency::Deuteranopia => [[0.0, 0.0, 0.0], [0.7, 1.0, 0.0], [0.7, 0.0, 1.0]],
+            Deficiency::Tritanopia => [[1.0, 0.0, 0.7], [0.0, 1.0, 0.7], [0.0, 0.0, 0.0]],
+        }
+    }
+}
+
+fn multiply(matrix: &[[f64; 3]; 3], vector: [f64; 3]) -> [f64; 3] {
+    let row = |index: usize| {
+        let row = &matrix[index];
+        row[0] * vector[0] + row[1] * vector[1] + row[2] * vector[2]
+    };
+
+    [row(0), row(1), row(2)]
+}
+
+// Per-user color transform: exact colors are first remapped (e.g. status
+// colors to ones the user can tell apart), then everything is daltonized.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VisionProfile {
+    pub deficiency: Option<Deficiency>,
+    pub strength: f64,
+    pub remap: Vec<(Color, Color)>,
+}
+
+impl VisionProfile {
+    // $RAZER_TEST_VISION, or vision.toml in the user's config directory.
+    pub fn default_path() -> Option<PathBuf> {
+        config_path("RAZER_TEST_VISION", "vision.toml")
+    }
+
+    pub fn load_default() -> Result<Option<VisionProfile>> {
+        match VisionProfile::default_path() {
+            Some(ref path) if path.exists() => Ok(Some(VisionProfile::load(path)?)),
+            _ => Ok(None),
+        }
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<VisionProfile> {
+        let path = path.as_ref();
+
+        VisionProfile::parse(&fs::read_to_string(path)?)
+            .chain_err(|| ErrorKind::InvalidVisionProfile(format!("in '{}'", path.display())))
+    }
+
+    pub fn parse(content: &str) -> Result<VisionProfile> {
+        let file: VisionFile = toml::from_str(content)?;
+        let strength = file.strength.unwrap_or(1.0);
+
+        if strength.is_nan() || !(0.0..=1.0).contains(&strength) {
+            return Err(ErrorKind::InvalidVisionProfile(format!("strength {} is not between 0 and 1", strength)).into());
+        }
+        let remap = file
+            .remap
+            .iter()
+            .map(|entry| Ok((Color::parse(&entry.from)?, Color::parse(&entry.to)?)))
+            .collect::<Result<Vec<(Color, Color)>>>()?;
+
+        Ok(VisionProfile {
+            deficiency: match file.deficiency {
+                Some(deficiency) => Some(deficiency.parse()?),
+                None => None,
+            },
+            strength,
+            remap,
+        })
+    }
+
+    fn daltonize(&self, color: Color, deficiency: Deficiency) -> Color {
+        let (red, green, blue) = color.to_linear();
+        let original = [red, green, blue];
+        let simulated = multiply(&deficiency.simulation(), original);
+        let error = [
+            original[0] - simulated[0],
+            original[1] - simulated[1],
+            original[2] - simulated[2],
+        ];
+        let correction = multiply(&deficiency.shift(), error);
+
+        Color::from_linear(
+            original[0] + correction[0] * self.strength,
+            original[1] + correction[1] * self.strength,
+            original[2] + correction[2] * self.strength,
+        )
+    }
+}
+
+impl ColorFilter for VisionProfile {
+    fn apply(&self, color: Color) -> Color {
+        let color = match self.remap.iter().find(|&&(from, _)| from == color) {
+            Some(&(_, to)) => to,
+            None => color,
+        };
+
+        match self.deficiency {
+            Some(deficiency) => self.daltonize(color, deficiency),
+            None => color,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: Color = Color { red: 255, green: 0, blue: 0 };
+    const GREEN: Color = Color { red: 0, green: 255, blue: 0 };
+
+    fn profile(deficiency: &str) -> VisionProfile {
+        VisionProfile::parse(&format!("deficiency = \"{}\"", deficiency)).unwrap()
+    }
+
+    #[test]
+    fn passes_grays_through() {
+        for deficiency in &["protanopia", "deuteranopia", "tritanopia"] {
+            let profile = profile(deficiency);
+
+            for &level in &[0, 1, 17, 128, 200, 254, 255] {
+                let gray = Color::new(level, level, level);
+                assert_eq!(profile.apply(gray), gray, "{} {}", deficiency, level);
+            }
+        }
+    }
+
+    #[test]
+    fn shifts_red_and_green_apart() {
+        let expected = [
+            ("protanopia", Color::new(255, 184, 203), Color::new(0, 184, 0)),
+            ("deuteranopia", Color::new(255, 112, 180), Color::new(0, 221, 0)),
+            ("tritanopia", Color::new(223, 77, 0), Color::new(0, 201, 0)),
+        ];
+
+        for &(deficiency, red, green) in &expected {
+            let profile = profile(deficiency);
+            assert_eq!((profile.apply(RED), profile.apply(GREEN)), (red, green), "{}", deficiency);
+        }
+    }
+
+    #[test]
+    fn scales_by_strength() {
+        let none = VisionProfile::parse("deficiency = \"protanopia\"\nstrength = 0").unwrap();
+        let half = VisionProfile::parse("deficiency = \"protanopia\"\nstrength = 0.5").unwrap();
+        let shifted = half.apply(RED);
+
+        assert_eq!(none.apply(RED), RED);
+        assert!(shifted.blue > 0 && shifted.blue < 203);
+        assert!(VisionProfile::parse("strength = 1.5").is_err());
+        assert!(VisionProfile::parse("deficiency = \"achromatopsia\"").is_err());
+    }
+
+    #[test]
+    fn remaps_exact_colors() {
+        let profile = VisionProfile::parse("[[remap]]\nfrom = \"255,0,0\"\nto = \"#0000ff\"").unwrap();
+
+        assert_eq!(profile.remap, vec![(RED, Color::new(0, 0, 255))]);
+        assert_eq!(profile.apply(RED), Color::new(0, 0, 255));
+        assert_eq!(profile.apply(Color::new(254, 0, 0)), Color::new(254, 0, 0));
+        assert_eq!(profile.apply(GREEN), GREEN);
+
+        // Remapped colors are daltonized like any other.
+        let both = VisionProfile::parse("deficiency = \"deuteranopia\"\n[[remap]]\nfrom = \"0,0,255\"\nto = \"red\"");
+        assert_eq!(both.unwrap().apply(Color::new(0, 0, 255)), Color::new(255, 112, 180));
+    }
+}
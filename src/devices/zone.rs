@@ -0,0 +1,170 @@
+use super::frame::Geometry;
+use super::keymap::{Key, Keymap};
+use errors::{ErrorKind, Result};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum Zone {
+    #[default]
+    All,
+    Row(usize),
+    Column(usize),
+    Keys(Vec<String>),
+    Group(KeyGroup),
+}
+
+// Keys with the same role on every keyboard, so e.g. themes work for all of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyGroup {
+    Alphas,
+    Numbers,
+    Modifiers,
+    FunctionRow,
+    Navigation,
+    Numpad,
+    Macro,
+    Media,
+    Logo,
+    // LEDs that are not keys: rows without keys (e.g. underglow), or the
+    // whole matrix of devices without a keymap (e.g. mouse strips).
+    Strips,
+}
+
+impl KeyGroup {
+    fn contains(self, key: &Key) -> bool {
+        let name = key.name;
+
+        match self {
+            KeyGroup::Alphas => name.len() == 1 && name.chars().all(|c| c.is_ascii_lowercase()),
+            KeyGroup::Numbers => name.len() == 1 && name.chars().all(|c| c.is_ascii_digit()),
+            KeyGroup::Modifiers => [
+                "leftshift",
+                "rightshift",
+                "leftctrl",
+                "rightctrl",
+                "leftalt",
+                "rightalt",
+                "leftmeta",
+                "rightmeta",
+                "fn",
+                "compose",
+                "capslock",
+            ].contains(&name),
+            KeyGroup::FunctionRow => name == "esc" || (name.starts_with('f') && name[1..].parse::<u8>().is_ok()),
+            KeyGroup::Navigation => [
+                "up", "down", "left", "right", "insert", "delete", "home", "end", "pageup", "pagedown",
+            ].contains(&name),
+            KeyGroup::Numpad => name.starts_with("kp") || name == "numlock",
+            KeyGroup::Macro => name.starts_with('m') && name[1..].parse::<u8>().is_ok(),
+            KeyGroup::Media => ["previoussong", "playpause", "nextsong", "mute"].contains(&name),
+            KeyGroup::Logo => name == "logo",
+            KeyGroup::Strips => false,
+        }
+    }
+
+    fn cells(self, geometry: Geometry, keymap: Option<&Keymap>) -> Vec<(usize, usize)> {
+        let inside = |row: usize, col: usize| row < geometry.rows && col < geometry.cols;
+
+        match (self, keymap) {
+            (KeyGroup::Strips, Some(keymap)) => (0..geometry.rows)
+                .filter(|&row| keymap.keys().iter().all(|key| key.row != row))
+                .flat_map(|row| (0..geometry.cols).map(move |col| (row, col)))
+                .collect(),
+            (KeyGroup::Strips, None) => Zone::All.cells(geometry, None),
+            (_, Some(keymap)) => keymap
+                .keys()
+                .iter()
+                .filter(|key| self.contains(key) && inside(key.row, key.col))
+                .map(|key| (key.row, key.col))
+                .collect(),
+            (_, None) => Vec::new(),
+        }
+    }
+}
+
+impl FromStr for KeyGroup {
+    type Err = ::errors::Error;
+
+    fn from_str(s: &str) -> Result<KeyGroup> {
+        match s {
+            "alphas" => Ok(KeyGroup::Alphas),
+            "numbers" => Ok(KeyGroup::Numbers),
+            "modifiers" => Ok(KeyGroup::Modifiers),
+            "frow" | "f-row" => Ok(KeyGroup::FunctionRow),
+            "navigation" => Ok(KeyGroup::Navigation),
+            "numpad" => Ok(KeyGroup::Numpad),
+            "macro" => Ok(KeyGroup::Macro),
+            "media" => Ok(KeyGroup::Media),
+            "logo" => Ok(KeyGroup::Logo),
+            "strips" => Ok(KeyGroup::Strips),
+            _ => Err(ErrorKind::InvalidZone(s.to_string()).into()),
+        }
+    }
+}
+
+impl Zone {
+    pub fn is_all(&self) -> bool {
+        *self == Zone::All
+    }
+
+    // Matrix cells covered by the zone, cells outside of the geometry are skipped.
+    pub fn cells(&self, geometry: Geometry, keymap: Option<&Keymap>) -> Vec<(usize, usize)> {
+        let mut cells = Vec::new();
+
+        match *self {
+            Zone::All => {
+                for row in 0..geometry.rows {
+                    for col in 0..geometry.cols {
+                        cells.push((row, col));
+                    }
+                }
+            }
+            Zone::Row(row) if row < geometry.rows => {
+                for col in 0..geometry.cols {
+                    cells.push((row, col));
+                }
+            }
+            Zone::Column(col) if col < geometry.cols => {
+                for row in 0..geometry.rows {
+                    cells.push((row, col));
+                }
+            }
+            Zone::Group(group) => cells = group.cells(geometry, keymap),
+            Zone::Keys(ref names) => {
+                if let Some(keymap) = keymap {
+                    for name in names {
+                        match keymap.by_name(name) {
+                            Some(key) if key.row < geometry.rows && key.col < geometry.cols => {
+                                cells.push((key.row, key.col))
+                            }
+                            _ => (),
+                        }
+                    }
+                }
+            }
+            _ => (),
+        }
+
+        cells
+    }
+}
+
+impl FromStr for Zone {
+    type Err = ::errors::Error;
+
+    fn from_str(s: &str) -> Result<Zone> {
+        let invalid = || ErrorKind::InvalidZone(s.to_string());
+        let mut parts = s.splitn(2, ':');
+        let kind = parts.next().unwrap_or("").trim();
+        let value = parts.next().map(str::trim);
+
+        match (kind, value) {
+            ("all", None) => Ok(Zone::All),
+            ("row", Some(row)) => Ok(Zone::Row(row.parse().map_err(|_| invalid())?)),
+            ("col", Some(col)) => Ok(Zone::Column(col.parse().map_err(|_| invalid())?)),
+            ("keys", Some(keys)) => Ok(Zone::Keys(keys.split(',').map(|key| key.trim().to_string()).collect())),
+            (group, None) => Ok(Zone::Group(group.parse().map_err(|_| invalid())?)),
+            _ => Err(invalid().into()),
+        }
+    }
+}
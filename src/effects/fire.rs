@@ -0,0 +1,91 @@
+use super::palette;
+use super::params::EffectParams;
+use super::random::Random;
+use animation::Effect;
+use devices::Frame;
+use errors::Result;
+
+pub struct FireParams {
+    pub cooling: f64,
+    pub sparking: f64,
+    pub rate: f64,
+    pub seed: u64,
+}
+
+impl FireParams {
+    pub fn from_params(params: &EffectParams) -> Result<FireParams> {
+        params.check_known(&["cooling", "sparking", "rate", "seed"])?;
+
+        Ok(FireParams {
+            cooling: params.get("cooling", 0.25)?,
+            sparking: params.get("sparking", 0.6)?,
+            rate: params.get_positive("rate", 30.0)?,
+            seed: params.get("seed", 1)?,
+        })
+    }
+}
+
+pub struct Fire {
+    params: FireParams,
+    random: Random,
+    heat: Vec<f64>,
+    steps: u64,
+}
+
+impl Fire {
+    pub fn new(params: FireParams) -> Fire {
+        let random = Random::new(params.seed);
+
+        Fire {
+            params,
+            random,
+            heat: Vec::new(),
+            steps: 0,
+        }
+    }
+
+    fn step(&mut self, rows: usize, cols: usize) {
+        for cell in self.heat.iter_mut() {
+            *cell = (*cell - self.random.next_f64() * self.params.cooling / rows as f64 * 2.0).max(0.0);
+        }
+        // Heat rises from the bottom row towards row 0, spreading a little sideways.
+        for row in 0..rows - 1 {
+            for col in 0..cols {
+                let left = self.heat[(row + 1) * cols + col.saturating_sub(1)];
+                let below = self.heat[(row + 1) * cols + col];
+                let right = self.heat[(row + 1) * cols + (col + 1).min(cols - 1)];
+
+                self.heat[row * cols + col] = (left + 2.0 * below + right) / 4.0;
+            }
+        }
+        for col in 0..cols {
+            if self.random.next_f64() < self.params.sparking {
+                let cell = &mut self.heat[(rows - 1) * cols + col];
+
+                *cell = (*cell + 0.5 + self.random.next_f64() * 0.5).min(1.0);
+            }
+        }
+    }
+}
+
+impl Effect for Fire {
+    fn render(&mut self, t: f64, frame: &mut Frame) {
+        let (rows, cols) = (frame.rows(), frame.cols());
+
+        if self.heat.len() != rows * cols {
+            self.heat = vec![0.0; rows * cols];
+        }
+        let target = (t * self.params.rate) as u64;
+        // Do not try to catch up on more than a second worth of steps.
+        self.steps = self.steps.max(target.saturating_sub(self.params.rate as u64));
+        while self.steps < target {
+            self.step(rows, cols);
+            self.steps += 1;
+        }
+        for row in 0..rows {
+            for col in 0..cols {
+                frame.set(row, col, palette::heat(self.heat[row * cols + col]));
+            }
+        }
+    }
+}
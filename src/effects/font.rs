@@ -0,0 +1,87 @@
+// A tiny 5 pixel high font, small enough for the 6 rows of a keyboard matrix.
+pub const FONT_HEIGHT: usize = 5;
+
+static GLYPHS: &[(char, [&str; FONT_HEIGHT])] = &[
+    ('A', [".#.", "#.#", "###", "#.#", "#.#"]),
+    ('B', ["##.", "#.#", "##.", "#.#", "##."]),
+    ('C', [".##", "#..", "#..", "#..", ".##"]),
+    ('D', ["##.", "#.#", "#.#", "#.#", "##."]),
+    ('E', ["###", "#..", "##.", "#..", "###"]),
+    ('F', ["###", "#..", "##.", "#..", "#.."]),
+    ('G', [".##", "#..", "#.#", "#.#", ".##"]),
+    ('H', ["#.#", "#.#", "###", "#.#", "#.#"]),
+    ('I', ["###", ".#.", ".#.", ".#.", "###"]),
+    ('J', ["..#", "..#", "..#", "#.#", ".#."]),
+    ('K', ["#.#", "#.#", "##.", "#.#", "#.#"]),
+    ('L', ["#..", "#..", "#..", "#..", "###"]),
+    ('M', ["#...#", "##.##", "#.#.#", "#...#", "#...#"]),
+    ('N', ["#..#", "##.#", "#.##", "#..#", "#..#"]),
+    ('O', [".#.", "#.#", "#.#", "#.#", ".#."]),
+    ('P', ["##.", "#.#", "##.", "#..", "#.."]),
+    ('Q', [".#.", "#.#", "#.#", "##.", ".##"]),
+    ('R', ["##.", "#.#", "##.", "#.#", "#.#"]),
+    ('S', [".##", "#..", ".#.", "..#", "##."]),
+    ('T', ["###", ".#.", ".#.", ".#.", ".#."]),
+    ('U', ["#.#", "#.#", "#.#", "#.#", "###"]),
+    ('V', ["#.#", "#.#", "#.#", "#.#", ".#."]),
+    ('W', ["#...#", "#...#", "#.#.#", "##.##", "#...#"]),
+    ('X', ["#.#", "#.#", ".#.", "#.#", "#.#"]),
+    ('Y', ["#.#", "#.#", ".#.", ".#.", ".#."]),
+    ('Z', ["###", "..#", ".#.", "#..", "###"]),
+    ('0', ["###", "#.#", "#.#", "#.#", "###"]),
+    ('1', [".#.", "##.", ".#.", ".#.", "###"]),
+    ('2', ["##.", "..#", ".#.", "#..", "###"]),
+    ('3', ["##.", "..#", ".#.", "..#", "##."]),
+    ('4', ["#.#", "#.#", "###", "..#", "..#"]),
+    ('5', ["###", "#..", "##.", "..#", "##."]),
+    ('6', [".##", "#..", "###", "#.#", "###"]),
+    ('7', ["###", "..#", ".#.", ".#.", ".#."]),
+    ('8', ["###", "#.#", "###", "#.#", "###"]),
+    ('9', ["###", "#.#", "###", "..#", "##."]),
+    (' ', ["..", "..", "..", "..", ".."]),
+    ('!', ["#", "#", "#", ".", "#"]),
+    ('?', ["##.", "..#", ".#.", "...", ".#."]),
+    ('.', [".", ".", ".", ".", "#"]),
+    (',', ["..", "..", "..", ".#", "#."]),
+    (':', [".", "#", ".", "#", "."]),
+    ('\'', ["#", "#", ".", ".", "."]),
+    ('-', ["...", "...", "###", "...", "..."]),
+    ('+', ["...", ".#.", "###", ".#.", "..."]),
+    ('=', ["...", "###", "...", "###", "..."]),
+    ('_', ["...", "...", "...", "...", "###"]),
+    ('/', ["..#", "..#", ".#.", "#..", "#.."]),
+    ('%', ["#.#", "..#", ".#.", "#..", "#.#"]),
+    ('(', [".#", "#.", "#.", "#.", ".#"]),
+    (')', ["#.", ".#", ".#", ".#", "#."]),
+];
+
+// Unknown characters are shown as '?'.
+fn glyph(c: char) -> &'static [&'static str; FONT_HEIGHT] {
+    let c = c.to_ascii_uppercase();
+    let find = |c: char| GLYPHS.iter().find(|&&(glyph, _)| glyph == c);
+
+    &find(c).or_else(|| find('?')).unwrap().1
+}
+
+// Renders the text as columns of lit pixels, top to bottom, one blank column between characters.
+pub fn render_text(text: &str) -> Vec<[bool; FONT_HEIGHT]> {
+    let mut columns = Vec::new();
+
+    for (i, c) in text.chars().enumerate() {
+        if i > 0 {
+            columns.push([false; FONT_HEIGHT]);
+        }
+        let rows = glyph(c);
+
+        for x in 0..rows[0].len() {
+            let mut column = [false; FONT_HEIGHT];
+
+            for (y, row) in rows.iter().enumerate() {
+                column[y] = row.as_bytes()[x] == b'#';
+            }
+            columns.push(column);
+        }
+    }
+
+    columns
+}
@@ -0,0 +1,55 @@
+use super::params::EffectParams;
+use animation::Effect;
+use devices::{Color, Frame, Interpolation};
+use errors::Result;
+
+pub struct ScrollingGradientParams {
+    pub from: Color,
+    pub to: Color,
+    pub interpolation: Interpolation,
+    pub angle: f64,
+    pub length: f64,
+    pub speed: f64,
+}
+
+impl ScrollingGradientParams {
+    pub fn from_params(params: &EffectParams) -> Result<ScrollingGradientParams> {
+        params.check_known(&["from", "to", "interpolation", "angle", "length", "speed"])?;
+
+        Ok(ScrollingGradientParams {
+            from: params.get_color("from", Color::new(255, 0, 0))?,
+            to: params.get_color("to", Color::new(0, 0, 255))?,
+            interpolation: params.get("interpolation", Interpolation::Rgb)?,
+            angle: params.get("angle", 0.0)?,
+            length: params.get_positive("length", 22.0)?,
+            speed: params.get("speed", 0.25)?,
+        })
+    }
+}
+
+pub struct ScrollingGradient {
+    params: ScrollingGradientParams,
+}
+
+impl ScrollingGradient {
+    pub fn new(params: ScrollingGradientParams) -> ScrollingGradient {
+        ScrollingGradient { params }
+    }
+}
+
+impl Effect for ScrollingGradient {
+    fn render(&mut self, t: f64, frame: &mut Frame) {
+        let (sin, cos) = self.params.angle.to_radians().sin_cos();
+
+        for row in 0..frame.rows() {
+            for col in 0..frame.cols() {
+                let position = col as f64 * cos + row as f64 * sin;
+                let phase = position / self.params.length - t * self.params.speed;
+                // Triangle wave, so the gradient repeats without a hard edge.
+                let value = 1.0 - (2.0 * (phase - phase.floor()) - 1.0).abs();
+
+                frame.set(row, col, self.params.from.interpolate(self.params.to, value, self.params.interpolation));
+            }
+        }
+    }
+}
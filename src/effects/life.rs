@@ -0,0 +1,111 @@
+use super::params::EffectParams;
+use super::random::Random;
+use animation::Effect;
+use devices::{Color, Frame};
+use errors::Result;
+
+pub struct LifeParams {
+    pub color: Color,
+    pub rate: f64,
+    pub density: f64,
+    pub seed: u64,
+}
+
+impl LifeParams {
+    pub fn from_params(params: &EffectParams) -> Result<LifeParams> {
+        params.check_known(&["color", "rate", "density", "seed"])?;
+
+        Ok(LifeParams {
+            color: params.get_color("color", Color::new(255, 255, 255))?,
+            rate: params.get_positive("rate", 4.0)?,
+            density: params.get("density", 0.35)?,
+            seed: params.get("seed", 1)?,
+        })
+    }
+}
+
+pub struct Life {
+    params: LifeParams,
+    random: Random,
+    cells: Vec<bool>,
+    history: Vec<Vec<bool>>,
+    generations: u64,
+}
+
+impl Life {
+    pub fn new(params: LifeParams) -> Life {
+        let random = Random::new(params.seed);
+
+        Life {
+            params,
+            random,
+            cells: Vec::new(),
+            history: Vec::new(),
+            generations: 0,
+        }
+    }
+
+    fn seed(&mut self, len: usize) {
+        let density = self.params.density;
+        let random = &mut self.random;
+
+        self.cells = (0..len).map(|_| random.next_f64() < density).collect();
+        self.history.clear();
+    }
+
+    fn step(&mut self, rows: usize, cols: usize) {
+        let mut next = vec![false; rows * cols];
+
+        // The board wraps around at the edges.
+        for row in 0..rows {
+            for col in 0..cols {
+                let mut neighbours = 0;
+
+                for dr in &[rows - 1, 0, 1] {
+                    for dc in &[cols - 1, 0, 1] {
+                        if (*dr, *dc) != (0, 0) && self.cells[(row + dr) % rows * cols + (col + dc) % cols] {
+                            neighbours += 1;
+                        }
+                    }
+                }
+                next[row * cols + col] = neighbours == 3 || (neighbours == 2 && self.cells[row * cols + col]);
+            }
+        }
+
+        // Start over once the board died out or settled into a short cycle.
+        if !next.iter().any(|c| *c) || next == self.cells || self.history.contains(&next) {
+            self.seed(rows * cols);
+            return;
+        }
+        self.history.push(::std::mem::replace(&mut self.cells, next));
+        if self.history.len() > 4 {
+            self.history.remove(0);
+        }
+    }
+}
+
+impl Effect for Life {
+    fn render(&mut self, t: f64, frame: &mut Frame) {
+        let (rows, cols) = (frame.rows(), frame.cols());
+
+        if self.cells.len() != rows * cols {
+            self.seed(rows * cols);
+        }
+        let target = (t * self.params.rate) as u64;
+        self.generations = self.generations.max(target.saturating_sub(self.params.rate as u64));
+        while self.generations < target {
+            self.step(rows, cols);
+            self.generations += 1;
+        }
+        for row in 0..rows {
+            for col in 0..cols {
+                let color = if self.cells[row * cols + col] {
+                    self.params.color
+                } else {
+                    Color::default()
+                };
+                frame.set(row, col, color);
+            }
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use super::font::{self, FONT_HEIGHT};
+use super::params::EffectParams;
+use animation::Effect;
+use devices::{Color, Frame};
+use errors::{ErrorKind, Result};
+
+pub struct MarqueeParams {
+    pub text: String,
+    pub color: Color,
+    pub background: Color,
+    // Columns per second
+    pub speed: f64,
+}
+
+impl MarqueeParams {
+    pub fn from_params(params: &EffectParams) -> Result<MarqueeParams> {
+        params.check_known(&["text", "color", "background", "speed"])?;
+
+        let params = MarqueeParams {
+            text: params.get("text", "hello".to_string())?,
+            color: params.get_color("color", Color::new(255, 255, 255))?,
+            background: params.get_color("background", Color::new(0, 0, 0))?,
+            speed: params.get("speed", 8.0)?,
+        };
+        params.validate()?;
+        Ok(params)
+    }
+
+    // Text that doesn't move left never finishes a pass.
+    pub fn validate(&self) -> Result<()> {
+        if self.speed.is_nan() || self.speed <= 0.0 {
+            return Err(ErrorKind::InvalidParameter("speed".to_string(), self.speed.to_string()).into());
+        }
+        Ok(())
+    }
+}
+
+// Text entering on the right and scrolling to the left until it has left the matrix, then again.
+pub struct Marquee {
+    params: MarqueeParams,
+    columns: Vec<[bool; FONT_HEIGHT]>,
+}
+
+impl Marquee {
+    pub fn new(params: MarqueeParams) -> Marquee {
+        let columns = font::render_text(&params.text);
+
+        Marquee { params, columns }
+    }
+
+    // Seconds for the text to scroll once across a matrix `cols` wide.
+    pub fn pass_length(&self, cols: usize) -> f64 {
+        (self.columns.len() + cols) as f64 / self.params.speed
+    }
+}
+
+impl Effect for Marquee {
+    fn render(&mut self, t: f64, frame: &mut Frame) {
+        let (rows, cols) = (frame.rows(), frame.cols());
+        let period = self.columns.len() + cols;
+        let step = (t * self.params.speed).max(0.0) as usize % period;
+        // Center vertically, leaning towards the bottom where the main block of keys is.
+        let top = (rows as isize - FONT_HEIGHT as isize + 1) / 2;
+
+        frame.fill(self.params.background);
+
+        for col in 0..cols {
+            let x = col + step;
+            if x < cols || x - cols >= self.columns.len() {
+                continue;
+            }
+            for (y, &lit) in self.columns[x - cols].iter().enumerate() {
+                let row = top + y as isize;
+
+                if lit && row >= 0 && (row as usize) < rows {
+                    frame.set(row as usize, col, self.params.color);
+                }
+            }
+        }
+    }
+}
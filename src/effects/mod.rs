@@ -0,0 +1,104 @@
+mod fire;
+mod font;
+mod gradient;
+mod life;
+mod marquee;
+mod noise;
+pub mod palette;
+mod params;
+mod plasma;
+mod rain;
+mod random;
+mod ripple;
+mod script;
+mod stack;
+
+pub use self::fire::{Fire, FireParams};
+pub use self::gradient::{ScrollingGradient, ScrollingGradientParams};
+pub use self::life::{Life, LifeParams};
+pub use self::marquee::{Marquee, MarqueeParams};
+pub use self::noise::{Noise, NoiseParams};
+pub use self::params::EffectParams;
+pub use self::plasma::{Plasma, PlasmaParams};
+pub use self::rain::{Rain, RainParams};
+pub use self::ripple::{Ripple, RippleParams};
+pub use self::script::ScriptEffect;
+pub use self::stack::LayerStack;
+
+use animation::Effect;
+use errors::{ErrorKind, Result};
+
+pub struct EffectInfo {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub params: &'static str,
+    create: fn(&EffectParams) -> Result<Box<Effect>>,
+}
+
+impl EffectInfo {
+    pub fn create(&self, params: &EffectParams) -> Result<Box<Effect>> {
+        (self.create)(params)
+    }
+}
+
+pub static EFFECTS: &[EffectInfo] = &[
+    EffectInfo {
+        name: "fire",
+        description: "flames rising from the bottom row",
+        params: "cooling=0.25 sparking=0.6 rate=30 seed=1",
+        create: |params| Ok(Box::new(Fire::new(FireParams::from_params(params)?))),
+    },
+    EffectInfo {
+        name: "rain",
+        description: "drops with fading trails falling down the columns",
+        params: "color=0,255,0 density=0.15 speed=8 trail=3 seed=1",
+        create: |params| Ok(Box::new(Rain::new(RainParams::from_params(params)?))),
+    },
+    EffectInfo {
+        name: "plasma",
+        description: "classic sine plasma",
+        params: "speed=1 scale=0.5",
+        create: |params| Ok(Box::new(Plasma::new(PlasmaParams::from_params(params)?))),
+    },
+    EffectInfo {
+        name: "noise",
+        description: "animated value noise between two colors",
+        params: "from=0,0,64 to=0,255,255 interpolation=rgb speed=0.5 scale=0.25 seed=1",
+        create: |params| Ok(Box::new(Noise::new(NoiseParams::from_params(params)?))),
+    },
+    EffectInfo {
+        name: "ripple",
+        description: "rings expanding from random keys",
+        params: "color=0,128,255 rate=1.5 speed=10 width=1.5 seed=1",
+        create: |params| Ok(Box::new(Ripple::new(RippleParams::from_params(params)?))),
+    },
+    EffectInfo {
+        name: "gradient",
+        description: "two color gradient scrolling at an arbitrary angle (degrees)",
+        params: "from=255,0,0 to=0,0,255 interpolation=rgb angle=0 length=22 speed=0.25",
+        create: |params| {
+            Ok(Box::new(ScrollingGradient::new(ScrollingGradientParams::from_params(
+                params,
+            )?)))
+        },
+    },
+    EffectInfo {
+        name: "life",
+        description: "Conway's Game of Life on a wrapping board",
+        params: "color=255,255,255 rate=4 density=0.35 seed=1",
+        create: |params| Ok(Box::new(Life::new(LifeParams::from_params(params)?))),
+    },
+    EffectInfo {
+        name: "marquee",
+        description: "text scrolling from right to left",
+        params: "text=hello color=255,255,255 background=0,0,0 speed=8",
+        create: |params| Ok(Box::new(Marquee::new(MarqueeParams::from_params(params)?))),
+    },
+];
+
+pub fn find_effect(name: &str) -> Result<&'static EffectInfo> {
+    EFFECTS
+        .iter()
+        .find(|effect| effect.name == name)
+        .ok_or_else(|| ErrorKind::UnknownEffect(name.to_string()).into())
+}
@@ -0,0 +1,73 @@
+use super::params::EffectParams;
+use super::random::hash3;
+use animation::Effect;
+use devices::{Color, Frame, Interpolation};
+use errors::Result;
+
+pub struct NoiseParams {
+    pub from: Color,
+    pub to: Color,
+    pub interpolation: Interpolation,
+    pub speed: f64,
+    pub scale: f64,
+    pub seed: u64,
+}
+
+impl NoiseParams {
+    pub fn from_params(params: &EffectParams) -> Result<NoiseParams> {
+        params.check_known(&["from", "to", "interpolation", "speed", "scale", "seed"])?;
+
+        Ok(NoiseParams {
+            from: params.get_color("from", Color::new(0, 0, 64))?,
+            to: params.get_color("to", Color::new(0, 255, 255))?,
+            interpolation: params.get("interpolation", Interpolation::Rgb)?,
+            speed: params.get("speed", 0.5)?,
+            scale: params.get("scale", 0.25)?,
+            seed: params.get("seed", 1)?,
+        })
+    }
+}
+
+pub struct Noise {
+    params: NoiseParams,
+}
+
+impl Noise {
+    pub fn new(params: NoiseParams) -> Noise {
+        Noise { params }
+    }
+
+    // Trilinearly interpolated value noise in [0, 1].
+    fn sample(&self, x: f64, y: f64, z: f64) -> f64 {
+        let (x0, y0, z0) = (x.floor(), y.floor(), z.floor());
+        let (fx, fy, fz) = (smooth(x - x0), smooth(y - y0), smooth(z - z0));
+        let (x0, y0, z0) = (x0 as i64, y0 as i64, z0 as i64);
+        let corner = |dx: i64, dy: i64, dz: i64| hash3(x0 + dx, y0 + dy, z0 + dz, self.params.seed);
+        let lerp = |a: f64, b: f64, t: f64| a + (b - a) * t;
+
+        let x00 = lerp(corner(0, 0, 0), corner(1, 0, 0), fx);
+        let x10 = lerp(corner(0, 1, 0), corner(1, 1, 0), fx);
+        let x01 = lerp(corner(0, 0, 1), corner(1, 0, 1), fx);
+        let x11 = lerp(corner(0, 1, 1), corner(1, 1, 1), fx);
+
+        lerp(lerp(x00, x10, fy), lerp(x01, x11, fy), fz)
+    }
+}
+
+fn smooth(t: f64) -> f64 {
+    t * t * (3.0 - 2.0 * t)
+}
+
+impl Effect for Noise {
+    fn render(&mut self, t: f64, frame: &mut Frame) {
+        let z = t * self.params.speed;
+
+        for row in 0..frame.rows() {
+            for col in 0..frame.cols() {
+                let value = self.sample(col as f64 * self.params.scale, row as f64 * self.params.scale, z);
+
+                frame.set(row, col, self.params.from.interpolate(self.params.to, value, self.params.interpolation));
+            }
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use devices::{split_colors, Color, Interpolation};
+use errors::{ErrorKind, Result};
+
+// Black body style ramp: black, red, yellow, white.
+pub fn heat(value: f64) -> Color {
+    let value = value.clamp(0.0, 1.0) * 3.0;
+
+    if value < 1.0 {
+        Color::from_f64(value, 0.0, 0.0)
+    } else if value < 2.0 {
+        Color::from_f64(1.0, value - 1.0, 0.0)
+    } else {
+        Color::from_f64(1.0, 1.0, value - 2.0)
+    }
+}
+
+// Evenly spaced color stops, sampled with linear interpolation.
+#[derive(Debug, Clone)]
+pub struct Ramp {
+    stops: Vec<Color>,
+}
+
+impl Ramp {
+    pub fn new(stops: Vec<Color>) -> Ramp {
+        Ramp { stops }
+    }
+
+    // Colors separated by whitespace or ';', e.g. "0,255,0 255,255,0 255,0,0".
+    pub fn parse(ramp_str: &str) -> Result<Ramp> {
+        let stops = split_colors(ramp_str)
+            .into_iter()
+            .map(Color::parse)
+            .collect::<Result<Vec<Color>>>()?;
+
+        if stops.is_empty() {
+            return Err(ErrorKind::InvalidColorFormat(ramp_str.to_string(), "no colors".to_string()).into());
+        }
+        Ok(Ramp::new(stops))
+    }
+
+    pub fn sample(&self, t: f64) -> Color {
+        Color::gradient(&self.stops, t, Interpolation::Rgb)
+    }
+}
@@ -0,0 +1,89 @@
+use devices::Color;
+use errors::{ErrorKind, Result, ResultExt};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Default)]
+pub struct EffectParams {
+    values: HashMap<String, String>,
+}
+
+impl EffectParams {
+    pub fn new() -> EffectParams {
+        Default::default()
+    }
+
+    pub fn parse<'a, I>(args: I) -> Result<EffectParams>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        let mut params = EffectParams::new();
+
+        for arg in args {
+            match arg.find('=') {
+                Some(pos) => params.set(arg[..pos].trim(), arg[pos + 1..].trim()),
+                None => return Err(ErrorKind::InvalidParameter(arg.to_string(), String::new()).into()),
+            }
+        }
+
+        Ok(params)
+    }
+
+    pub fn set(&mut self, name: &str, value: &str) {
+        self.values.insert(name.to_string(), value.to_string());
+    }
+
+    pub fn check_known(&self, known: &[&str]) -> Result<()> {
+        for name in self.values.keys() {
+            if !known.contains(&name.as_str()) {
+                return Err(ErrorKind::UnknownParameter(name.clone()).into());
+            }
+        }
+        Ok(())
+    }
+
+    pub fn get<T: FromStr>(&self, name: &str, default: T) -> Result<T> {
+        match self.values.get(name) {
+            Some(value) => value
+                .parse::<T>()
+                .map_err(|_| ErrorKind::InvalidParameter(name.to_string(), value.clone()).into()),
+            None => Ok(default),
+        }
+    }
+
+    // A number that must be finite and above zero, like the speeds and rates
+    // that would otherwise stall an effect or let it grow without bound.
+    pub fn get_positive(&self, name: &str, default: f64) -> Result<f64> {
+        let value: f64 = self.get(name, default)?;
+
+        if !value.is_finite() || value <= 0.0 {
+            return Err(ErrorKind::InvalidParameter(name.to_string(), value.to_string()).into());
+        }
+        Ok(value)
+    }
+
+    pub fn get_color(&self, name: &str, default: Color) -> Result<Color> {
+        match self.values.get(name) {
+            Some(value) => {
+                Color::parse(value).chain_err(|| ErrorKind::InvalidParameter(name.to_string(), value.clone()))
+            }
+            None => Ok(default),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_numbers_that_are_not_positive() {
+        for value in &["0", "-1", "nan", "inf"] {
+            let params = EffectParams::parse(vec![format!("speed={}", value).as_str()]).unwrap();
+            assert!(params.get_positive("speed", 1.0).is_err(), "{}", value);
+        }
+        let params = EffectParams::parse(vec!["speed=2.5"]).unwrap();
+        assert_eq!(params.get_positive("speed", 1.0).unwrap(), 2.5);
+        assert_eq!(params.get_positive("rate", 1.0).unwrap(), 1.0);
+    }
+}
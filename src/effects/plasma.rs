@@ -0,0 +1,52 @@
+use super::params::EffectParams;
+use animation::Effect;
+use devices::{Color, Frame};
+use errors::Result;
+
+pub struct PlasmaParams {
+    pub speed: f64,
+    pub scale: f64,
+}
+
+impl PlasmaParams {
+    pub fn from_params(params: &EffectParams) -> Result<PlasmaParams> {
+        params.check_known(&["speed", "scale"])?;
+
+        Ok(PlasmaParams {
+            speed: params.get("speed", 1.0)?,
+            scale: params.get("scale", 0.5)?,
+        })
+    }
+}
+
+pub struct Plasma {
+    params: PlasmaParams,
+}
+
+impl Plasma {
+    pub fn new(params: PlasmaParams) -> Plasma {
+        Plasma { params }
+    }
+}
+
+impl Effect for Plasma {
+    fn render(&mut self, t: f64, frame: &mut Frame) {
+        let t = t * self.params.speed;
+        let scale = self.params.scale;
+
+        for row in 0..frame.rows() {
+            for col in 0..frame.cols() {
+                let x = col as f64 * scale;
+                let y = row as f64 * scale;
+                let cx = x + 0.5 * (t / 5.0).sin() * 10.0;
+                let cy = y + 0.5 * (t / 3.0).cos() * 10.0;
+                let value = (x + t).sin()
+                    + ((y + t) / 2.0).sin()
+                    + ((x + y + t) / 2.0).sin()
+                    + ((cx * cx + cy * cy).sqrt() + t).sin();
+
+                frame.set(row, col, Color::from_hsv((value / 8.0 + 0.5) * 360.0, 1.0, 1.0));
+            }
+        }
+    }
+}
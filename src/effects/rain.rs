@@ -0,0 +1,92 @@
+use super::params::EffectParams;
+use super::random::Random;
+use animation::Effect;
+use devices::{Color, Frame};
+use errors::Result;
+
+pub struct RainParams {
+    pub color: Color,
+    pub density: f64,
+    pub speed: f64,
+    pub trail: f64,
+    pub seed: u64,
+}
+
+impl RainParams {
+    pub fn from_params(params: &EffectParams) -> Result<RainParams> {
+        params.check_known(&["color", "density", "speed", "trail", "seed"])?;
+
+        Ok(RainParams {
+            color: params.get_color("color", Color::new(0, 255, 0))?,
+            density: params.get("density", 0.15)?,
+            speed: params.get_positive("speed", 8.0)?,
+            trail: params.get("trail", 3.0)?,
+            seed: params.get("seed", 1)?,
+        })
+    }
+}
+
+struct Drop {
+    col: usize,
+    start: f64,
+}
+
+pub struct Rain {
+    params: RainParams,
+    random: Random,
+    drops: Vec<Drop>,
+    last: f64,
+}
+
+impl Rain {
+    pub fn new(params: RainParams) -> Rain {
+        let random = Random::new(params.seed);
+
+        Rain {
+            params,
+            random,
+            drops: Vec::new(),
+            last: 0.0,
+        }
+    }
+}
+
+impl Effect for Rain {
+    fn render(&mut self, t: f64, frame: &mut Frame) {
+        let (rows, cols) = (frame.rows(), frame.cols());
+        let elapsed = (t - self.last).clamp(0.0, 1.0);
+
+        // `density` is the expected number of new drops per column and second.
+        for col in 0..cols {
+            if self.random.next_f64() < self.params.density * elapsed {
+                self.drops.push(Drop { col, start: t });
+            }
+        }
+        self.last = t;
+
+        let speed = self.params.speed;
+        let trail = self.params.trail;
+        self.drops.retain(|drop| (t - drop.start) * speed - trail < rows as f64);
+
+        frame.fill(Color::default());
+        for drop in self.drops.iter() {
+            let head = (t - drop.start) * speed;
+
+            for row in 0..rows {
+                let behind = head - row as f64;
+
+                if behind >= 0.0 && behind < trail {
+                    let color = self.params.color.scale(1.0 - behind / trail);
+
+                    if intensity(color) > intensity(frame.get(row, drop.col)) {
+                        frame.set(row, drop.col, color);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn intensity(color: Color) -> u32 {
+    u32::from(color.red) + u32::from(color.green) + u32::from(color.blue)
+}
@@ -0,0 +1,95 @@
+use super::params::EffectParams;
+use super::random::Random;
+use animation::Effect;
+use devices::{Color, Frame};
+use errors::Result;
+
+pub struct RippleParams {
+    pub color: Color,
+    pub rate: f64,
+    pub speed: f64,
+    pub width: f64,
+    pub seed: u64,
+}
+
+impl RippleParams {
+    pub fn from_params(params: &EffectParams) -> Result<RippleParams> {
+        params.check_known(&["color", "rate", "speed", "width", "seed"])?;
+
+        Ok(RippleParams {
+            color: params.get_color("color", Color::new(0, 128, 255))?,
+            rate: params.get("rate", 1.5)?,
+            speed: params.get_positive("speed", 10.0)?,
+            width: params.get("width", 1.5)?,
+            seed: params.get("seed", 1)?,
+        })
+    }
+}
+
+struct Wave {
+    row: usize,
+    col: usize,
+    start: f64,
+}
+
+pub struct Ripple {
+    params: RippleParams,
+    random: Random,
+    waves: Vec<Wave>,
+    last: f64,
+}
+
+impl Ripple {
+    pub fn new(params: RippleParams) -> Ripple {
+        let random = Random::new(params.seed);
+
+        Ripple {
+            params,
+            random,
+            waves: Vec::new(),
+            last: 0.0,
+        }
+    }
+
+    pub fn trigger(&mut self, row: usize, col: usize, t: f64) {
+        self.waves.push(Wave { row, col, start: t });
+    }
+}
+
+impl Effect for Ripple {
+    fn render(&mut self, t: f64, frame: &mut Frame) {
+        let (rows, cols) = (frame.rows(), frame.cols());
+        let elapsed = (t - self.last).clamp(0.0, 1.0);
+
+        if self.random.next_f64() < self.params.rate * elapsed {
+            let (row, col) = (self.random.below(rows), self.random.below(cols));
+
+            self.trigger(row, col, t);
+        }
+        self.last = t;
+
+        let reach = (rows * rows + cols * cols) as f64;
+        let (speed, width) = (self.params.speed, self.params.width);
+        self.waves.retain(|wave| {
+            let radius = (t - wave.start) * speed - width;
+            radius * radius < reach
+        });
+
+        for row in 0..rows {
+            for col in 0..cols {
+                let mut value = 0.0f64;
+
+                for wave in self.waves.iter() {
+                    let dr = row as f64 - wave.row as f64;
+                    let dc = col as f64 - wave.col as f64;
+                    let distance = ((dr * dr + dc * dc).sqrt() - (t - wave.start) * speed).abs();
+
+                    if distance < width {
+                        value = value.max(1.0 - distance / width);
+                    }
+                }
+                frame.set(row, col, self.params.color.scale(value));
+            }
+        }
+    }
+}
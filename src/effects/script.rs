@@ -0,0 +1,238 @@
+use animation::Effect;
+use devices::{Color, Frame, Interpolation, Keymap};
+use errors::{ErrorKind, Result};
+use rhai::{Array, CallFnOptions, Dynamic, Engine, EvalAltResult, Map, Scope, AST};
+use std::cell::RefCell;
+use std::fs;
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::SystemTime;
+
+// Seconds between two checks whether the script file has been modified.
+const RELOAD_INTERVAL: f64 = 0.5;
+
+// Handle passed to the script, shares the frame currently being rendered.
+#[derive(Clone)]
+struct ScriptFrame {
+    frame: Rc<RefCell<Frame>>,
+    keymap: Option<&'static Keymap>,
+}
+
+impl ScriptFrame {
+    fn contains(&self, row: i64, col: i64) -> bool {
+        let frame = self.frame.borrow();
+
+        row >= 0 && col >= 0 && (row as usize) < frame.rows() && (col as usize) < frame.cols()
+    }
+
+    fn set(&mut self, row: i64, col: i64, color: Color) {
+        if self.contains(row, col) {
+            self.frame.borrow_mut().set(row as usize, col as usize, color);
+        }
+    }
+
+    fn get(&mut self, row: i64, col: i64) -> Color {
+        if self.contains(row, col) {
+            self.frame.borrow().get(row as usize, col as usize)
+        } else {
+            Color::default()
+        }
+    }
+
+    fn key(&mut self, name: &str) -> Dynamic {
+        match self.keymap.and_then(|keymap| keymap.by_name(name)) {
+            Some(key) => {
+                let mut map = Map::new();
+                map.insert("row".into(), (key.row as i64).into());
+                map.insert("col".into(), (key.col as i64).into());
+                map.into()
+            }
+            None => Dynamic::UNIT,
+        }
+    }
+
+    fn set_key(&mut self, name: &str, color: Color) -> bool {
+        match self.keymap.and_then(|keymap| keymap.by_name(name)) {
+            Some(key) if self.contains(key.row as i64, key.col as i64) => {
+                self.frame.borrow_mut().set(key.row, key.col, color);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+fn channel(value: i64) -> u8 {
+    value.clamp(0, 255) as u8
+}
+
+// mix(a, b, t, "perceptual") etc., see Interpolation.
+fn mix_with(a: Color, b: Color, t: f64, mode: &str) -> ::std::result::Result<Color, Box<EvalAltResult>> {
+    let mode: Interpolation = mode.parse().map_err(|error: ::errors::Error| error.to_string())?;
+
+    Ok(a.interpolate(b, t, mode))
+}
+
+fn create_engine() -> Engine {
+    let mut engine = Engine::new();
+
+    engine.set_max_operations(1_000_000);
+    // Debug builds of rhai default to much lower limits, nested loops in render would not compile.
+    engine.set_max_expr_depths(64, 32);
+    engine
+        .register_type_with_name::<Color>("Color")
+        .register_fn("rgb", |r: i64, g: i64, b: i64| Color::new(channel(r), channel(g), channel(b)))
+        .register_fn("hsv", Color::from_hsv)
+        .register_fn("hsl", Color::from_hsl)
+        .register_fn("kelvin", Color::from_kelvin)
+        .register_fn("mix", Color::mix)
+        .register_fn("mix", mix_with)
+        .register_fn("gradient", |stops: Array, t: f64| {
+            let stops: Vec<Color> = stops.into_iter().filter_map(|stop| stop.try_cast::<Color>()).collect();
+            Color::gradient(&stops, t, Interpolation::Rgb)
+        })
+        .register_fn("scale", Color::scale)
+        .register_fn("dim", Color::dim)
+        .register_fn("gamma", Color::gamma)
+        .register_get("hue", |color: &mut Color| color.to_hsv().0)
+        .register_get("saturation", |color: &mut Color| color.to_hsv().1)
+        .register_get("value", |color: &mut Color| color.to_hsv().2)
+        .register_get("lightness", |color: &mut Color| color.to_hsl().2)
+        .register_get("red", |color: &mut Color| i64::from(color.red))
+        .register_get("green", |color: &mut Color| i64::from(color.green))
+        .register_get("blue", |color: &mut Color| i64::from(color.blue))
+        .register_fn("to_string", |color: &mut Color| {
+            format!("{},{},{}", color.red, color.green, color.blue)
+        });
+    engine
+        .register_type_with_name::<ScriptFrame>("Frame")
+        .register_get("rows", |frame: &mut ScriptFrame| frame.frame.borrow().rows() as i64)
+        .register_get("cols", |frame: &mut ScriptFrame| frame.frame.borrow().cols() as i64)
+        .register_fn("set", ScriptFrame::set)
+        .register_fn("get", ScriptFrame::get)
+        .register_fn("fill", |frame: &mut ScriptFrame, color: Color| frame.frame.borrow_mut().fill(color))
+        .register_fn("key", ScriptFrame::key)
+        .register_fn("set_key", ScriptFrame::set_key);
+
+    engine
+}
+
+// Effect defined by a Rhai script. The script has to define `render(frame, t)`
+// and may define `init(frame)`, whose result is available as `this` in render.
+pub struct ScriptEffect {
+    path: PathBuf,
+    engine: Engine,
+    ast: AST,
+    scope: Scope<'static>,
+    state: Option<Dynamic>,
+    frame: ScriptFrame,
+    modified: Option<SystemTime>,
+    last_check: f64,
+    failed: bool,
+}
+
+impl ScriptEffect {
+    pub fn load<P: AsRef<Path>>(path: P, keymap: Option<&'static Keymap>) -> Result<ScriptEffect> {
+        let path = path.as_ref().to_path_buf();
+        let engine = create_engine();
+        let modified = fs::metadata(&path)?.modified().ok();
+        let ast = compile(&engine, &path)?;
+
+        Ok(ScriptEffect {
+            path,
+            engine,
+            ast,
+            scope: Scope::new(),
+            state: None,
+            frame: ScriptFrame {
+                frame: Rc::new(RefCell::new(Frame::new(Default::default()))),
+                keymap,
+            },
+            modified,
+            last_check: 0.0,
+            failed: false,
+        })
+    }
+
+    fn reload_if_modified(&mut self) {
+        let modified = match fs::metadata(&self.path).and_then(|metadata| metadata.modified()) {
+            Ok(modified) => modified,
+            Err(_) => return,
+        };
+        if Some(modified) == self.modified {
+            return;
+        }
+        self.modified = Some(modified);
+        match compile(&self.engine, &self.path) {
+            Ok(ast) => {
+                info!("Reloaded {}", self.path.display());
+                self.ast = ast;
+                self.scope = Scope::new();
+                self.state = None;
+                self.failed = false;
+            }
+            Err(error) => warn!("{}", error),
+        }
+    }
+
+    fn call(&mut self, t: f64) -> Result<()> {
+        let has_init = self
+            .ast
+            .iter_functions()
+            .any(|function| function.name == "init" && function.params.len() == 1);
+        let mut state = match self.state.take() {
+            Some(state) => state,
+            None if has_init => self
+                .engine
+                .call_fn_with_options::<Dynamic>(
+                    CallFnOptions::new().eval_ast(false),
+                    &mut self.scope,
+                    &self.ast,
+                    "init",
+                    (self.frame.clone(),),
+                ).map_err(|error| ErrorKind::Script(error.to_string()))?,
+            None => Map::new().into(),
+        };
+        let result = self.engine.call_fn_with_options::<Dynamic>(
+            CallFnOptions::new().eval_ast(false).bind_this_ptr(&mut state),
+            &mut self.scope,
+            &self.ast,
+            "render",
+            (self.frame.clone(), t),
+        );
+        self.state = Some(state);
+
+        result.map(|_| ()).map_err(|error| ErrorKind::Script(error.to_string()).into())
+    }
+}
+
+fn compile(engine: &Engine, path: &Path) -> Result<AST> {
+    let source = fs::read_to_string(path)?;
+
+    engine
+        .compile(&source)
+        .map_err(|error| ErrorKind::Script(format!("{}: {}", path.display(), error)).into())
+}
+
+impl Effect for ScriptEffect {
+    fn render(&mut self, t: f64, frame: &mut Frame) {
+        if t - self.last_check >= RELOAD_INTERVAL {
+            self.last_check = t;
+            self.reload_if_modified();
+        }
+        if self.failed {
+            return;
+        }
+
+        mem::swap(&mut *self.frame.frame.borrow_mut(), frame);
+        let result = self.call(t);
+        mem::swap(&mut *self.frame.frame.borrow_mut(), frame);
+
+        // Errors are reported once, the script is not run again until it changes.
+        if let Err(error) = result {
+            warn!("{}: {}", self.path.display(), error);
+            self.failed = true;
+        }
+    }
+}
@@ -0,0 +1,267 @@
+use super::find_effect;
+use super::params::EffectParams;
+use animation::{BlendMode, Compositor, Effect, LayerId};
+use devices::{Frame, Keymap, Zone};
+use errors::{ErrorKind, Result};
+use serde_json;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use toml;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum ParamValue {
+    Text(String),
+    Number(f64),
+    Flag(bool),
+}
+
+impl ParamValue {
+    fn to_param(&self) -> String {
+        match *self {
+            ParamValue::Text(ref text) => text.clone(),
+            ParamValue::Number(number) => number.to_string(),
+            ParamValue::Flag(flag) => flag.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct LayerFile {
+    effect: String,
+    blend: Option<String>,
+    opacity: Option<f64>,
+    zone: Option<String>,
+    start: Option<f64>,
+    end: Option<f64>,
+    fade: Option<f64>,
+    #[serde(default)]
+    params: HashMap<String, ParamValue>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct LayerStackFile {
+    #[serde(rename = "layer", alias = "layers", default)]
+    layers: Vec<LayerFile>,
+}
+
+struct LayerSpec {
+    effect: String,
+    params: EffectParams,
+    blend: BlendMode,
+    opacity: f64,
+    mask: Option<Zone>,
+    // Seconds into the animation the layer is shown from and until.
+    start: f64,
+    end: Option<f64>,
+    fade: f64,
+}
+
+impl LayerSpec {
+    fn is_scheduled(&self) -> bool {
+        self.start > 0.0 || self.end.is_some()
+    }
+}
+
+// Effects stacked bottom first as described in a toml or json file.
+pub struct LayerStack {
+    layers: Vec<LayerSpec>,
+}
+
+impl LayerStack {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<LayerStack> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)?;
+
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("json") => LayerStack::from_file(serde_json::from_str(&content)?),
+            _ => LayerStack::parse(&content),
+        }
+    }
+
+    pub fn parse(content: &str) -> Result<LayerStack> {
+        LayerStack::from_file(toml::from_str(content)?)
+    }
+
+    fn from_file(file: LayerStackFile) -> Result<LayerStack> {
+        let mut layers = Vec::new();
+
+        for layer in file.layers {
+            let mut params = EffectParams::new();
+            for (name, value) in layer.params.iter() {
+                params.set(name, &value.to_param());
+            }
+            // Fail on unknown effects or parameters right away.
+            find_effect(&layer.effect)?.create(&params)?;
+            let start = layer.start.unwrap_or(0.0);
+            let fade = layer.fade.unwrap_or(0.0);
+            if !(start >= 0.0 && fade >= 0.0 && layer.end.map(|end| end > start).unwrap_or(true)) {
+                return Err(ErrorKind::InvalidParameter(layer.effect, "start, end or fade".to_string()).into());
+            }
+
+            layers.push(LayerSpec {
+                effect: layer.effect,
+                params,
+                blend: match layer.blend {
+                    Some(blend) => blend.parse()?,
+                    None => BlendMode::Normal,
+                },
+                opacity: layer.opacity.unwrap_or(1.0),
+                mask: match layer.zone {
+                    Some(zone) => Some(zone.parse()?),
+                    None => None,
+                },
+                start,
+                end: layer.end,
+                fade,
+            });
+        }
+
+        Ok(LayerStack { layers })
+    }
+
+    pub fn build(&self, keymap: Option<&'static Keymap>) -> Result<StackEffect> {
+        let compositor = Compositor::new(keymap);
+        let mut scheduled = Vec::new();
+
+        for layer in self.layers.iter() {
+            let effect = find_effect(&layer.effect)?.create(&layer.params)?;
+
+            if layer.is_scheduled() {
+                scheduled.push(ScheduledLayer {
+                    effect: Some(effect),
+                    id: None,
+                    blend: layer.blend,
+                    opacity: layer.opacity,
+                    mask: layer.mask.clone(),
+                    start: layer.start,
+                    end: layer.end,
+                    fade: layer.fade,
+                });
+            } else {
+                compositor.add_layer(effect, layer.blend, layer.opacity, layer.mask.clone());
+            }
+        }
+
+        Ok(StackEffect { compositor, scheduled })
+    }
+}
+
+struct ScheduledLayer {
+    // Taken when the layer is added to the compositor.
+    effect: Option<Box<Effect>>,
+    id: Option<LayerId>,
+    blend: BlendMode,
+    opacity: f64,
+    mask: Option<Zone>,
+    start: f64,
+    end: Option<f64>,
+    fade: f64,
+}
+
+impl ScheduledLayer {
+    // Fades in after `start` and out before `end`.
+    fn opacity_at(&self, t: f64) -> f64 {
+        if self.fade <= 0.0 {
+            return self.opacity;
+        }
+        let fade_in = (t - self.start) / self.fade;
+        let fade_out = self.end.map(|end| (end - t) / self.fade).unwrap_or(1.0);
+
+        self.opacity * fade_in.min(fade_out).clamp(0.0, 1.0)
+    }
+}
+
+// The composited stack. Layers with a start or end time are added on top
+// while the animation runs and removed again once they end, e.g. a
+// notification shown for a few seconds.
+pub struct StackEffect {
+    compositor: Compositor,
+    scheduled: Vec<ScheduledLayer>,
+}
+
+impl StackEffect {
+    fn update(&mut self, t: f64) {
+        for layer in self.scheduled.iter_mut() {
+            let ended = layer.end.map(|end| t >= end).unwrap_or(false);
+
+            if layer.id.is_none() && t >= layer.start && !ended {
+                if let Some(effect) = layer.effect.take() {
+                    let opacity = layer.opacity_at(t);
+                    layer.id = Some(self.compositor.add_layer(effect, layer.blend, opacity, layer.mask.clone()));
+                }
+            }
+            if let Some(id) = layer.id {
+                if ended {
+                    self.compositor.remove_layer(id);
+                    layer.id = None;
+                } else {
+                    self.compositor.set_opacity(id, layer.opacity_at(t));
+                }
+            }
+        }
+    }
+}
+
+impl Effect for StackEffect {
+    fn render(&mut self, t: f64, frame: &mut Frame) {
+        self.update(t);
+        self.compositor.render(t, frame);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use devices::{Color, Geometry};
+
+    fn render(effect: &mut StackEffect, t: f64) -> Frame {
+        let mut frame = Frame::new(Geometry::new(2, 3));
+
+        effect.render(t, &mut frame);
+        frame
+    }
+
+    const STACK: &str = r#"
+        [[layer]]
+        effect = "gradient"
+        [layer.params]
+        from = "0,0,255"
+        to = "0,0,255"
+
+        [[layer]]
+        effect = "gradient"
+        zone = "row:0"
+        start = 1
+        end = 3
+        fade = 0.5
+        [layer.params]
+        from = "255,0,0"
+        to = "255,0,0"
+    "#;
+
+    #[test]
+    fn adds_and_removes_scheduled_layers() {
+        let stack = LayerStack::parse(STACK).unwrap();
+        let mut effect = stack.build(None).unwrap();
+        let (red, blue) = (Color::new(255, 0, 0), Color::new(0, 0, 255));
+
+        assert_eq!(render(&mut effect, 0.5).get(0, 0), blue);
+        assert_eq!(render(&mut effect, 1.25).get(0, 0), Color::new(128, 0, 128));
+        let shown = render(&mut effect, 2.0);
+        assert_eq!(shown.get(0, 0), red);
+        assert_eq!(shown.get(1, 0), blue);
+        assert_eq!(render(&mut effect, 2.75).get(0, 2), Color::new(128, 0, 128));
+        assert_eq!(render(&mut effect, 3.0).get(0, 0), blue);
+        // Removed layers do not come back.
+        assert_eq!(render(&mut effect, 2.0).get(0, 0), blue);
+    }
+
+    #[test]
+    fn rejects_end_before_start() {
+        let stack = "[[layer]]\neffect = \"plasma\"\nstart = 2\nend = 1\n";
+
+        assert!(LayerStack::parse(stack).is_err());
+    }
+}
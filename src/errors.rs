@@ -4,8 +4,13 @@ error_chain! {
     }
 
     foreign_links {
+        Io(::std::io::Error);
         CString(::std::ffi::NulError);
         CStr(::std::ffi::FromBytesWithNulError);
+        Toml(::toml::de::Error);
+        Json(::serde_json::Error);
+        Gif(::gif::EncodingError);
+        Png(::png::EncodingError);
     }
 
     errors {
@@ -24,9 +29,109 @@ error_chain! {
             display("not supported")
         }
 
-        InvalidColorFormat {
+        InvalidColorFormat(color: String, reason: String) {
             description("invalid color format")
-            display("invalid color format")
+            display("invalid color '{}': {}", color, reason)
+        }
+
+        InvalidFrameRate(fps: u32) {
+            description("invalid frame rate")
+            display("invalid frame rate: {}", fps)
+        }
+
+        UnknownDevice(name: String) {
+            description("unknown device")
+            display("unknown device: '{}'", name)
+        }
+
+        UnknownEffect(name: String) {
+            description("unknown effect")
+            display("unknown effect: '{}'", name)
+        }
+
+        UnknownParameter(name: String) {
+            description("unknown parameter")
+            display("unknown parameter: '{}'", name)
+        }
+
+        InvalidParameter(name: String, value: String) {
+            description("invalid parameter")
+            display("invalid parameter: '{}' = '{}'", name, value)
+        }
+
+        Script(message: String) {
+            description("script error")
+            display("script error: {}", message)
+        }
+
+        InvalidEasing(name: String) {
+            description("invalid easing")
+            display("invalid easing: '{}'", name)
+        }
+
+        InvalidBlendMode(name: String) {
+            description("invalid blend mode")
+            display("invalid blend mode: '{}'", name)
+        }
+
+        InvalidZone(zone: String) {
+            description("invalid zone")
+            display("invalid zone: '{}'", zone)
+        }
+
+        InvalidTimeline(message: String) {
+            description("invalid timeline")
+            display("invalid timeline: {}", message)
+        }
+
+        InvalidAudio(message: String) {
+            description("invalid audio input")
+            display("invalid audio input: {}", message)
+        }
+
+        InvalidSystemInfo(path: String) {
+            description("invalid system information")
+            display("invalid system information in '{}'", path)
+        }
+
+        GeometryMismatch {
+            description("frame geometry does not match device")
+            display("frame geometry does not match device")
+        }
+
+        InvalidKeyUsage(message: String) {
+            description("invalid key usage")
+            display("invalid key usage: {}", message)
+        }
+
+        InvalidCalibration(message: String) {
+            description("invalid calibration")
+            display("invalid calibration: {}", message)
+        }
+
+        InvalidVisionProfile(message: String) {
+            description("invalid vision profile")
+            display("invalid vision profile: {}", message)
+        }
+
+        InvalidTheme(message: String) {
+            description("invalid theme")
+            display("invalid theme: {}", message)
+        }
+
+        InvalidDesk(message: String) {
+            description("invalid desk layout")
+            display("invalid desk layout: {}", message)
+        }
+
+        InvalidBrightness(value: String) {
+            description("invalid brightness")
+            display("invalid brightness: '{}', expected a percentage between 0 and 100", value)
+        }
+
+        InvalidLedZone(name: String) {
+            description("invalid LED zone")
+            display("invalid LED zone: '{}'", name)
         }
     }
 }
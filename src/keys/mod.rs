@@ -0,0 +1,7 @@
+mod reactive;
+mod source;
+mod usage;
+
+pub use self::reactive::*;
+pub use self::source::*;
+pub use self::usage::*;
@@ -0,0 +1,234 @@
+use super::KeySource;
+use animation::Effect;
+use devices::{Color, Frame, Keymap};
+use effects::palette::Ramp;
+use effects::Ripple;
+use errors::{ErrorKind, Result};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+// An effect that reacts to presses of the key at the given matrix cell.
+pub trait Reaction: Effect {
+    fn press(&mut self, row: usize, col: usize, t: f64);
+}
+
+impl Reaction for Ripple {
+    fn press(&mut self, row: usize, col: usize, t: f64) {
+        self.trigger(row, col, t);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReactionKind {
+    Ripple,
+    Afterglow,
+    Heatmap,
+}
+
+impl FromStr for ReactionKind {
+    type Err = ::errors::Error;
+
+    fn from_str(s: &str) -> Result<ReactionKind> {
+        match s {
+            "ripple" => Ok(ReactionKind::Ripple),
+            "afterglow" => Ok(ReactionKind::Afterglow),
+            "heatmap" => Ok(ReactionKind::Heatmap),
+            _ => Err(ErrorKind::InvalidParameter("reaction".to_string(), s.to_string()).into()),
+        }
+    }
+}
+
+// Pressed keys light up and fade out over `fade` seconds.
+pub struct Afterglow {
+    color: Color,
+    fade: f64,
+    pressed: HashMap<(usize, usize), f64>,
+}
+
+impl Afterglow {
+    pub fn new(color: Color, fade: f64) -> Afterglow {
+        Afterglow {
+            color,
+            fade,
+            pressed: HashMap::new(),
+        }
+    }
+}
+
+impl Reaction for Afterglow {
+    fn press(&mut self, row: usize, col: usize, t: f64) {
+        self.pressed.insert((row, col), t);
+    }
+}
+
+impl Effect for Afterglow {
+    fn render(&mut self, t: f64, frame: &mut Frame) {
+        let fade = self.fade.max(0.001);
+
+        frame.fill(Color::default());
+        self.pressed.retain(|_, pressed| t - *pressed < fade);
+
+        for (&(row, col), &pressed) in self.pressed.iter() {
+            let value = 1.0 - ((t - pressed) / fade).max(0.0);
+            frame.set(row, col, self.color.scale(value));
+        }
+    }
+}
+
+// Every press heats the key up, heat halves every `half_life` seconds.
+pub struct TypingHeatmap {
+    ramp: Ramp,
+    half_life: f64,
+    heat: HashMap<(usize, usize), f64>,
+    last: f64,
+}
+
+// Presses until a key reaches the hot end of the ramp.
+const PRESSES_TO_MAX: f64 = 8.0;
+
+impl TypingHeatmap {
+    pub fn new(ramp: Ramp, half_life: f64) -> TypingHeatmap {
+        TypingHeatmap {
+            ramp,
+            half_life,
+            heat: HashMap::new(),
+            last: 0.0,
+        }
+    }
+
+    // Decays the heat up to `t`, so that presses aren't cooled for time before they happened.
+    fn cool(&mut self, t: f64) {
+        let elapsed = (t - self.last).max(0.0);
+        let decay = 0.5f64.powf(elapsed / self.half_life.max(0.001));
+        self.last = t;
+
+        for heat in self.heat.values_mut() {
+            *heat *= decay;
+        }
+        self.heat.retain(|_, heat| *heat > 0.001);
+    }
+}
+
+impl Reaction for TypingHeatmap {
+    fn press(&mut self, row: usize, col: usize, t: f64) {
+        self.cool(t);
+        let heat = self.heat.entry((row, col)).or_insert(0.0);
+        *heat = (*heat + 1.0 / PRESSES_TO_MAX).min(1.0);
+    }
+}
+
+impl Effect for TypingHeatmap {
+    fn render(&mut self, t: f64, frame: &mut Frame) {
+        self.cool(t);
+
+        frame.fill(self.ramp.sample(0.0));
+        for (&(row, col), &heat) in self.heat.iter() {
+            frame.set(row, col, self.ramp.sample(heat));
+        }
+    }
+}
+
+// Feeds key presses from `source` to the reaction, mapped to cells with the device keymap.
+pub struct Reactive {
+    source: Box<KeySource>,
+    keymap: &'static Keymap,
+    reaction: Box<Reaction>,
+}
+
+impl Reactive {
+    pub fn new(source: Box<KeySource>, keymap: &'static Keymap, reaction: Box<Reaction>) -> Reactive {
+        Reactive {
+            source,
+            keymap,
+            reaction,
+        }
+    }
+}
+
+impl Effect for Reactive {
+    fn render(&mut self, t: f64, frame: &mut Frame) {
+        for event in self.source.poll(t) {
+            if !event.pressed {
+                continue;
+            }
+            match self.keymap.by_code(event.code) {
+                Some(key) if key.row < frame.rows() && key.col < frame.cols() => {
+                    self.reaction.press(key.row, key.col, t)
+                }
+                _ => debug!("No LED for key code {}", event.code),
+            }
+        }
+        self.reaction.render(t, frame);
+    }
+}
+
+#[cfg(all(test, target_pointer_width = "64"))]
+mod tests {
+    use super::*;
+    use devices::{find_layouts, Layout};
+    use keys::ReplaySource;
+
+    const TYPING: &[u8] = include_bytes!("../../fixtures/keys/typing.evdev");
+    const A: (usize, usize) = (3, 2);
+    const ESC: (usize, usize) = (0, 1);
+
+    fn blackwidow() -> Layout {
+        find_layouts("blackwidow").unwrap().remove(0)
+    }
+
+    fn replay(layout: &Layout, reaction: Box<Reaction>) -> Reactive {
+        let source = ReplaySource::read(TYPING).unwrap();
+        Reactive::new(Box::new(source), layout.keymap.unwrap(), reaction)
+    }
+
+    fn lit(frame: &Frame) -> Vec<(usize, usize)> {
+        let mut cells = Vec::new();
+        for row in 0..frame.rows() {
+            for col in 0..frame.cols() {
+                if frame.get(row, col) != Color::default() {
+                    cells.push((row, col));
+                }
+            }
+        }
+        cells
+    }
+
+    #[test]
+    fn lights_the_pressed_keys() {
+        let layout = blackwidow();
+        let red = Color::new(255, 0, 0);
+        let mut reactive = replay(&layout, Box::new(Afterglow::new(red, 1.0)));
+        let mut frame = Frame::new(layout.geometry);
+
+        reactive.render(0.0, &mut frame);
+        assert_eq!(lit(&frame), vec![A]);
+        assert_eq!(frame.get(A.0, A.1), red);
+
+        // Releases don't restart the glow, and the unmapped key 240 is ignored.
+        reactive.render(0.5, &mut frame);
+        assert_eq!(lit(&frame), vec![ESC, A]);
+        assert_eq!(frame.get(ESC.0, ESC.1), red);
+        assert_eq!(frame.get(A.0, A.1), red.scale(0.5));
+
+        reactive.render(1.2, &mut frame);
+        assert_eq!(lit(&frame), vec![ESC]);
+        assert_eq!(frame.get(ESC.0, ESC.1), red.scale(0.3));
+    }
+
+    #[test]
+    fn heats_and_cools_the_pressed_keys() {
+        let layout = blackwidow();
+        let ramp = Ramp::new(vec![Color::new(0, 0, 0), Color::new(255, 0, 0)]);
+        let mut reactive = replay(&layout, Box::new(TypingHeatmap::new(ramp.clone(), 0.5)));
+        let mut frame = Frame::new(layout.geometry);
+
+        reactive.render(0.0, &mut frame);
+        assert_eq!(frame.get(A.0, A.1), ramp.sample(1.0 / 8.0));
+
+        // 'a' cooled for one half-life, escape was pressed this frame.
+        reactive.render(0.5, &mut frame);
+        assert_eq!(lit(&frame), vec![ESC, A]);
+        assert_eq!(frame.get(A.0, A.1), ramp.sample(1.0 / 16.0));
+        assert_eq!(frame.get(ESC.0, ESC.1), ramp.sample(1.0 / 8.0));
+    }
+}
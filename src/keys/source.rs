@@ -0,0 +1,191 @@
+use errors::Result;
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::path::Path;
+use std::sync::mpsc::{channel, Receiver};
+use std::thread;
+
+const EV_KEY: u16 = 0x01;
+
+// `struct input_event` starts with a timeval of two native words.
+#[cfg(target_pointer_width = "64")]
+const WORD: usize = 8;
+#[cfg(not(target_pointer_width = "64"))]
+const WORD: usize = 4;
+const EVENT_SIZE: usize = 2 * WORD + 8;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KeyEvent {
+    // Seconds since the first event of a recording, or the arrival time for live events.
+    pub time: f64,
+    pub code: u16,
+    pub pressed: bool,
+}
+
+fn little_endian(bytes: &[u8]) -> i64 {
+    let value = bytes
+        .iter()
+        .rev()
+        .fold(0u64, |value, &byte| value << 8 | u64::from(byte));
+    let shift = 64 - 8 * bytes.len() as u32;
+
+    // Sign extend
+    ((value << shift) as i64) >> shift
+}
+
+// Decodes the next key press or release, other events (and key repeats) are skipped.
+fn read_event<R: Read>(reader: &mut R) -> io::Result<Option<(f64, u16, i32)>> {
+    let mut raw = [0u8; EVENT_SIZE];
+
+    loop {
+        match reader.read_exact(&mut raw) {
+            Ok(()) => (),
+            Err(ref error) if error.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(error) => return Err(error),
+        }
+        let sec = little_endian(&raw[0..WORD]) as f64;
+        let usec = little_endian(&raw[WORD..2 * WORD]) as f64;
+        let kind = little_endian(&raw[2 * WORD..2 * WORD + 2]) as u16;
+        let code = little_endian(&raw[2 * WORD + 2..2 * WORD + 4]) as u16;
+        let value = little_endian(&raw[2 * WORD + 4..2 * WORD + 8]) as i32;
+
+        if kind == EV_KEY && value != 2 {
+            return Ok(Some((sec + usec / 1_000_000.0, code, value)));
+        }
+    }
+}
+
+pub trait KeySource {
+    // Key events that happened up to animation time `t`.
+    fn poll(&mut self, t: f64) -> Vec<KeyEvent>;
+}
+
+// Live events read from an evdev device like /dev/input/event3.
+pub struct EvdevSource {
+    events: Receiver<(u16, bool)>,
+}
+
+impl EvdevSource {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<EvdevSource> {
+        let mut file = File::open(path)?;
+        let (sender, events) = channel();
+
+        thread::spawn(move || loop {
+            match read_event(&mut file) {
+                Ok(Some((_, code, value))) => {
+                    if sender.send((code, value == 1)).is_err() {
+                        return;
+                    }
+                }
+                Ok(None) => return,
+                Err(error) => {
+                    warn!("Reading key events failed: {}", error);
+                    return;
+                }
+            }
+        });
+
+        Ok(EvdevSource { events })
+    }
+}
+
+impl KeySource for EvdevSource {
+    fn poll(&mut self, t: f64) -> Vec<KeyEvent> {
+        self.events
+            .try_iter()
+            .map(|(code, pressed)| KeyEvent {
+                time: t,
+                code,
+                pressed,
+            }).collect()
+    }
+}
+
+// Replays a recorded evdev stream (e.g. `cat /dev/input/event3 > keys.evdev`)
+// according to the timestamps, relative to the first event.
+pub struct ReplaySource {
+    events: Vec<KeyEvent>,
+    next: usize,
+}
+
+impl ReplaySource {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<ReplaySource> {
+        ReplaySource::read(BufReader::new(File::open(path)?))
+    }
+
+    pub fn read<R: Read>(mut reader: R) -> Result<ReplaySource> {
+        let mut events = Vec::new();
+        let mut start = None;
+
+        while let Some((time, code, value)) = read_event(&mut reader)? {
+            let start = *start.get_or_insert(time);
+
+            events.push(KeyEvent {
+                time: time - start,
+                code,
+                pressed: value == 1,
+            });
+        }
+
+        Ok(ReplaySource { events, next: 0 })
+    }
+
+    pub fn events(&self) -> &[KeyEvent] {
+        &self.events
+    }
+
+    pub fn length(&self) -> f64 {
+        self.events.last().map(|event| event.time).unwrap_or(0.0)
+    }
+}
+
+impl KeySource for ReplaySource {
+    fn poll(&mut self, t: f64) -> Vec<KeyEvent> {
+        let start = self.next;
+
+        while self.next < self.events.len() && self.events[self.next].time <= t {
+            self.next += 1;
+        }
+
+        self.events[start..self.next].to_vec()
+    }
+}
+
+// The fixture was recorded with the 64 bit layout of `struct input_event`.
+#[cfg(all(test, target_pointer_width = "64"))]
+mod tests {
+    use super::*;
+
+    const TYPING: &[u8] = include_bytes!("../../fixtures/keys/typing.evdev");
+
+    fn codes(events: &[KeyEvent]) -> Vec<(u16, bool)> {
+        events.iter().map(|event| (event.code, event.pressed)).collect()
+    }
+
+    #[test]
+    fn reads_recorded_presses_and_releases() {
+        let replay = ReplaySource::read(TYPING).unwrap();
+        let times: Vec<f64> = replay.events().iter().map(|event| event.time).collect();
+
+        // Scan codes, syncs and the key repeat are skipped.
+        assert_eq!(codes(replay.events()), vec![(30, true), (30, false), (1, true), (1, false), (240, true)]);
+        assert!(times.iter().zip([0.0, 0.1, 0.5, 0.6, 1.0].iter()).all(|(a, b)| (a - b).abs() < 1e-6));
+        assert!((replay.length() - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn replays_events_up_to_the_animation_time() {
+        let mut replay = ReplaySource::read(TYPING).unwrap();
+
+        assert_eq!(codes(&replay.poll(0.3)), vec![(30, true), (30, false)]);
+        assert!(replay.poll(0.3).is_empty());
+        assert_eq!(codes(&replay.poll(5.0)), vec![(1, true), (1, false), (240, true)]);
+    }
+
+    #[test]
+    fn ignores_truncated_events() {
+        let replay = ReplaySource::read(&TYPING[..TYPING.len() - 3]).unwrap();
+
+        assert_eq!(replay.events().len(), 5);
+    }
+}
@@ -0,0 +1,154 @@
+use super::ReplaySource;
+use animation::Effect;
+use devices::{Color, Frame, Keymap};
+use effects::palette::Ramp;
+use errors::{ErrorKind, Result};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+// Number of presses per evdev key code.
+#[derive(Debug, Clone, Default)]
+pub struct KeyUsage {
+    counts: HashMap<u16, u64>,
+}
+
+impl KeyUsage {
+    // A CSV file of `code,count` lines, anything else is read as a recorded evdev log.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<KeyUsage> {
+        let path = path.as_ref();
+
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("csv") => KeyUsage::parse_csv(&fs::read_to_string(path)?),
+            _ => Ok(KeyUsage::from_events(&ReplaySource::load(path)?)),
+        }
+    }
+
+    pub fn from_events(source: &ReplaySource) -> KeyUsage {
+        let mut usage = KeyUsage::default();
+
+        for event in source.events() {
+            if event.pressed {
+                *usage.counts.entry(event.code).or_insert(0) += 1;
+            }
+        }
+
+        usage
+    }
+
+    // Empty lines and '#' comments are skipped, as is the first line after
+    // them if it is a header.
+    pub fn parse_csv(content: &str) -> Result<KeyUsage> {
+        let mut usage = KeyUsage::default();
+        let mut first = true;
+
+        for (number, line) in content.lines().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let header = first;
+            first = false;
+            let invalid = || ErrorKind::InvalidKeyUsage(format!("line {}: '{}'", number + 1, line));
+            let mut fields = line.split(',').map(str::trim);
+            let code = fields.next().unwrap_or("");
+            let count = fields.next().ok_or_else(invalid)?;
+
+            match (code.parse::<u16>(), count.parse::<u64>()) {
+                (Ok(code), Ok(count)) => {
+                    let total = usage.counts.entry(code).or_insert(0);
+                    *total = total.saturating_add(count);
+                }
+                _ if header => continue,
+                _ => return Err(invalid().into()),
+            }
+        }
+
+        Ok(usage)
+    }
+
+    pub fn count(&self, code: u16) -> u64 {
+        self.counts.get(&code).cloned().unwrap_or(0)
+    }
+
+    pub fn max(&self) -> u64 {
+        self.counts.values().cloned().max().unwrap_or(0)
+    }
+}
+
+// Colors every key by its share of the most used key, optionally on a log
+// scale so that a few heavily used keys do not wash out the rest.
+pub struct UsageHeatmap {
+    usage: KeyUsage,
+    keymap: &'static Keymap,
+    ramp: Ramp,
+    logarithmic: bool,
+}
+
+impl UsageHeatmap {
+    pub fn new(usage: KeyUsage, keymap: &'static Keymap, ramp: Ramp, logarithmic: bool) -> UsageHeatmap {
+        UsageHeatmap {
+            usage,
+            keymap,
+            ramp,
+            logarithmic,
+        }
+    }
+
+    fn level(&self, count: u64) -> f64 {
+        let max = self.usage.max();
+
+        if max == 0 {
+            0.0
+        } else if self.logarithmic {
+            (count as f64).ln_1p() / (max as f64).ln_1p()
+        } else {
+            count as f64 / max as f64
+        }
+    }
+}
+
+impl Effect for UsageHeatmap {
+    fn render(&mut self, _t: f64, frame: &mut Frame) {
+        frame.fill(Color::default());
+
+        for key in self.keymap.keys() {
+            if key.code == 0 || key.row >= frame.rows() || key.col >= frame.cols() {
+                continue;
+            }
+            let color = self.ramp.sample(self.level(self.usage.count(key.code)));
+            frame.set(key.row, key.col, color);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn skips_comments_and_a_header() {
+        let usage = KeyUsage::parse_csv("# exported counts\n\ncode,count\n30, 12\n48,3\n30,4\n").unwrap();
+
+        assert_eq!((usage.count(30), usage.count(48), usage.count(17)), (16, 3, 0));
+        assert_eq!(usage.max(), 16);
+        assert_eq!(KeyUsage::parse_csv("30,1\n48,2").unwrap().count(48), 2);
+    }
+
+    #[test]
+    fn rejects_malformed_lines() {
+        let error = KeyUsage::parse_csv("code,count\n30,12\ncode,count\n").unwrap_err();
+        assert_eq!(error.to_string(), ErrorKind::InvalidKeyUsage("line 3: 'code,count'".to_string()).to_string());
+
+        assert!(KeyUsage::parse_csv("# counts\n30,12\n48,many\n").is_err());
+        assert!(KeyUsage::parse_csv("30\n").is_err());
+    }
+
+    #[test]
+    fn saturates_large_counts() {
+        let usage = KeyUsage::parse_csv(&format!("30,{}\n30,{}\n", u64::MAX, u64::MAX)).unwrap();
+
+        assert_eq!(usage.count(30), u64::MAX);
+    }
+}
@@ -1,36 +1,730 @@
+#![recursion_limit = "256"]
+
 extern crate clap;
 extern crate hidapi;
 #[macro_use]
 extern crate error_chain;
+extern crate gif;
 #[macro_use]
 extern crate lazy_static;
 extern crate env_logger;
 extern crate hex;
+extern crate libc;
 #[macro_use]
 extern crate log;
+extern crate png;
+extern crate rhai;
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde_json;
+extern crate toml;
 
+mod animation;
+mod audio;
 mod cli;
+mod desk;
 mod devices;
+mod effects;
 mod errors;
+mod keys;
+mod meter;
+mod render;
+mod sysmon;
+mod theme;
+mod timeline;
+mod timer;
+mod transition;
+
+use animation::Easing;
+use audio::PcmFormat;
+use clap::{App, Arg, ArgMatches, SubCommand};
+use devices::{Brightness, Color, Direction, Gradient, Interpolation, LedZone, Persistence, Sampling, Zone};
+use effects::palette::Ramp;
+use effects::{EffectParams, MarqueeParams};
+use meter::Thresholds;
+use std::path::PathBuf;
+use std::time::Duration;
+use sysmon::SystemPaths;
+use theme::Theme;
+use timer::{Flash, Phase};
+use transition::Transition;
+
+// Rejected by clap with the parse error, instead of a panic later on.
+fn valid_brightness(value: String) -> ::std::result::Result<(), String> {
+    value.parse::<Brightness>().map(|_| ()).map_err(|error| error.to_string())
+}
+
+fn valid_color(value: String) -> ::std::result::Result<(), String> {
+    Color::parse(&value).map(|_| ()).map_err(|error| error.to_string())
+}
+
+fn parse_seconds(value: &str) -> Option<Duration> {
+    value.parse::<f64>().ok().and_then(|seconds| Duration::try_from_secs_f64(seconds).ok())
+}
+
+// Negative, infinite or non-numeric seconds would make `Duration::from_secs_f64` panic.
+fn valid_seconds(value: String) -> ::std::result::Result<(), String> {
+    parse_seconds(&value)
+        .map(|_| ())
+        .ok_or_else(|| format!("{} is not a number of seconds", value))
+}
 
-use clap::{App, Arg, SubCommand};
-use devices::Color;
+fn seconds(matches: &ArgMatches, name: &str) -> Option<Duration> {
+    matches.value_of(name).and_then(parse_seconds)
+}
+
+fn valid_timer_length(value: String) -> ::std::result::Result<(), String> {
+    timer::parse_duration(&value).map(|_| ()).map_err(|error| error.to_string())
+}
+
+fn valid_count(value: String) -> ::std::result::Result<(), String> {
+    value
+        .parse::<u32>()
+        .map(|_| ())
+        .map_err(|_| format!("{} is not a whole number", value))
+}
+
+fn valid_rate(value: String) -> ::std::result::Result<(), String> {
+    match value.parse::<f64>() {
+        Ok(rate) if rate.is_finite() && rate > 0.0 => Ok(()),
+        _ => Err(format!("{} is not a positive number", value)),
+    }
+}
+
+fn persist_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name("persist")
+        .long("persist")
+        .help("store on the device, so it survives unplugging")
+}
+
+fn persistence(matches: &ArgMatches) -> Persistence {
+    if matches.is_present("persist") {
+        Persistence::Stored
+    } else {
+        Persistence::Transient
+    }
+}
 
 fn main() {
     let matches = App::new("razer_test test")
         .version("0.0.1")
         .about("Tests razer devices on a very low level")
         .arg(Arg::with_name("debug").short("D").long("debug").help("Enable debug"))
-        .subcommand(SubCommand::with_name("list").about("list all recognized devices"))
+        .arg(
+            Arg::with_name("preview")
+                .short("P")
+                .long("preview")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .help("also show animations in the terminal as this device (part of its name, or all)"),
+        ).subcommand(SubCommand::with_name("list").about("list all recognized devices"))
         .subcommand(SubCommand::with_name("get-brightness").about("get brightness"))
         .subcommand(
             SubCommand::with_name("set-brightness")
                 .about("set brightness")
-                .arg(Arg::with_name("brightness").required(true)),
+                .arg(persist_arg())
+                .arg(
+                    Arg::with_name("brightness")
+                        .required(true)
+                        .validator(valid_brightness)
+                        .help("percentage between 0 and 100, e.g. 40 or 40%"),
+                ).arg(
+                    Arg::with_name("zone")
+                        .long("zone")
+                        .takes_value(true)
+                        .possible_values(&["scroll-wheel", "logo", "backlight", "left-side", "right-side"])
+                        .help("only set this LED, on devices that have it"),
+                ).arg(
+                    Arg::with_name("fade")
+                        .long("fade")
+                        .takes_value(true)
+                        .validator(valid_seconds)
+                        .help("seconds to fade from the current (or --from) value, Ctrl-C stops the fade midway"),
+                ).arg(
+                    Arg::with_name("from")
+                        .long("from")
+                        .takes_value(true)
+                        .requires("fade")
+                        .validator(valid_brightness)
+                        .help("brightness to start the fade at"),
+                ).arg(
+                    Arg::with_name("easing")
+                        .long("easing")
+                        .takes_value(true)
+                        .default_value("ease-in-out")
+                        .possible_values(&["linear", "step", "ease-in", "ease-out", "ease-in-out", "sine"]),
+                ),
         ).subcommand(
             SubCommand::with_name("set-color")
                 .about("set color")
-                .arg(Arg::with_name("color").required(true)),
+                .arg(persist_arg())
+                .arg(Arg::with_name("color").required(true).validator(valid_color))
+                .arg(
+                    Arg::with_name("fade")
+                        .long("fade")
+                        .takes_value(true)
+                        .validator(valid_seconds)
+                        .help("seconds to fade from --from, Ctrl-C stops the fade midway"),
+                ).arg(
+                    Arg::with_name("from")
+                        .long("from")
+                        .takes_value(true)
+                        .validator(valid_color)
+                        .requires("fade")
+                        .help("color to start the fade at, black if omitted"),
+                ).arg(
+                    Arg::with_name("easing")
+                        .long("easing")
+                        .takes_value(true)
+                        .default_value("ease-in-out")
+                        .possible_values(&["linear", "step", "ease-in", "ease-out", "ease-in-out", "sine"]),
+                ).arg(
+                    Arg::with_name("fps")
+                        .long("fps")
+                        .takes_value(true)
+                        .default_value("30"),
+                ),
+        ).subcommand(
+            SubCommand::with_name("set-gradient")
+                .about("set a static gradient on all devices supporting custom frames")
+                .arg(persist_arg())
+                .arg(
+                    Arg::with_name("colors")
+                        .required(true)
+                        .multiple(true)
+                        .validator(valid_color)
+                        .help("evenly spaced color stops"),
+                ).arg(
+                    Arg::with_name("direction")
+                        .long("direction")
+                        .takes_value(true)
+                        .default_value("horizontal")
+                        .help("horizontal, vertical, radial or an angle in degrees (90 is top to bottom)"),
+                ).arg(
+                    Arg::with_name("interpolation")
+                        .long("interpolation")
+                        .takes_value(true)
+                        .default_value("rgb")
+                        .possible_values(&["rgb", "linear", "hsv", "perceptual"]),
+                ),
+        ).subcommand(
+            SubCommand::with_name("theme")
+                .about("apply a color theme to all devices")
+                .arg(persist_arg())
+                .arg(
+                    Arg::with_name("theme")
+                        .required(true)
+                        .help("theme file, or the name of one in the themes config directory"),
+                ),
+        ).subcommand(SubCommand::with_name("effects").about("list available software effects"))
+        .subcommand(
+            SubCommand::with_name("animate")
+                .about("run a software effect on all devices supporting custom frames")
+                .arg(Arg::with_name("effect").required(true))
+                .arg(
+                    Arg::with_name("param")
+                        .short("p")
+                        .long("param")
+                        .takes_value(true)
+                        .multiple(true)
+                        .number_of_values(1)
+                        .help("effect parameter as name=value"),
+                ).arg(
+                    Arg::with_name("desk")
+                        .long("desk")
+                        .help("run one effect across the devices placed in desk.toml of the config directory"),
+                ).arg(
+                    Arg::with_name("mirror")
+                        .long("mirror")
+                        .takes_value(true)
+                        .conflicts_with("desk")
+                        .help("render once for this device and stretch the frames to fit all others"),
+                ).arg(
+                    Arg::with_name("sampling")
+                        .long("sampling")
+                        .takes_value(true)
+                        .default_value("bilinear")
+                        .possible_values(&["nearest", "bilinear", "area"])
+                        .help("how mirrored frames are stretched"),
+                ).arg(
+                    Arg::with_name("fps")
+                        .long("fps")
+                        .takes_value(true)
+                        .default_value("30"),
+                ).arg(
+                    Arg::with_name("duration")
+                        .long("duration")
+                        .takes_value(true)
+                        .validator(valid_seconds)
+                        .help("seconds to run, forever if omitted"),
+                ),
+        ).subcommand(
+            SubCommand::with_name("script")
+                .about("run a Rhai script effect, reloaded whenever the file changes")
+                .arg(Arg::with_name("file").required(true))
+                .arg(
+                    Arg::with_name("fps")
+                        .long("fps")
+                        .takes_value(true)
+                        .default_value("30"),
+                ).arg(
+                    Arg::with_name("duration")
+                        .long("duration")
+                        .takes_value(true)
+                        .validator(valid_seconds)
+                        .help("seconds to run, forever if omitted"),
+                ),
+        ).subcommand(
+            SubCommand::with_name("play")
+                .about("play a keyframe timeline (toml or json)")
+                .arg(Arg::with_name("file").required(true))
+                .arg(
+                    Arg::with_name("fps")
+                        .long("fps")
+                        .takes_value(true)
+                        .default_value("30"),
+                ).arg(
+                    Arg::with_name("duration")
+                        .long("duration")
+                        .takes_value(true)
+                        .validator(valid_seconds)
+                        .help("seconds to run, defaults to the length of the timeline or forever if it loops"),
+                ),
+        ).subcommand(
+            SubCommand::with_name("compose")
+                .about("run a stack of blended effect layers described in a toml or json file")
+                .arg(Arg::with_name("file").required(true))
+                .arg(
+                    Arg::with_name("fps")
+                        .long("fps")
+                        .takes_value(true)
+                        .default_value("30"),
+                ).arg(
+                    Arg::with_name("duration")
+                        .long("duration")
+                        .takes_value(true)
+                        .validator(valid_seconds)
+                        .help("seconds to run, forever if omitted"),
+                ),
+        ).subcommand(
+            SubCommand::with_name("visualize")
+                .about("show the audio spectrum of a wav or raw PCM stream")
+                .arg(Arg::with_name("input").help("wav or raw PCM file, stdin if omitted or '-'"))
+                .arg(
+                    Arg::with_name("format")
+                        .long("format")
+                        .takes_value(true)
+                        .default_value("s16le")
+                        .help("sample format of raw PCM: u8, s16le, s32le or f32le"),
+                ).arg(
+                    Arg::with_name("rate")
+                        .long("rate")
+                        .takes_value(true)
+                        .default_value("44100")
+                        .help("sample rate of raw PCM"),
+                ).arg(
+                    Arg::with_name("channels")
+                        .long("channels")
+                        .takes_value(true)
+                        .default_value("2")
+                        .help("channels of raw PCM"),
+                ).arg(
+                    Arg::with_name("low")
+                        .long("low")
+                        .takes_value(true)
+                        .validator(valid_color)
+                        .default_value("0,255,0"),
+                ).arg(
+                    Arg::with_name("high")
+                        .long("high")
+                        .takes_value(true)
+                        .validator(valid_color)
+                        .default_value("255,0,0"),
+                ).arg(
+                    Arg::with_name("gain")
+                        .long("gain")
+                        .takes_value(true)
+                        .default_value("1.0"),
+                ).arg(
+                    Arg::with_name("fps")
+                        .long("fps")
+                        .takes_value(true)
+                        .default_value("30"),
+                ),
+        ).subcommand(
+            SubCommand::with_name("monitor")
+                .about("show cpu load per core, memory usage, memory pressure or temperatures")
+                .arg(
+                    Arg::with_name("metric")
+                        .required(true)
+                        .possible_values(&["cpu", "memory", "pressure", "temperature"]),
+                ).arg(
+                    Arg::with_name("ramp")
+                        .long("ramp")
+                        .takes_value(true)
+                        .default_value("0,255,0 255,255,0 255,0,0")
+                        .help("colors from low to high"),
+                ).arg(
+                    Arg::with_name("min")
+                        .long("min")
+                        .takes_value(true)
+                        .default_value("30")
+                        .help("temperature shown as low"),
+                ).arg(
+                    Arg::with_name("max")
+                        .long("max")
+                        .takes_value(true)
+                        .default_value("90")
+                        .help("temperature shown as high"),
+                ).arg(
+                    Arg::with_name("interval")
+                        .long("interval")
+                        .takes_value(true)
+                        .default_value("1")
+                        .help("seconds between samples"),
+                ).arg(
+                    Arg::with_name("proc")
+                        .long("proc")
+                        .takes_value(true)
+                        .default_value("/proc"),
+                ).arg(
+                    Arg::with_name("sys")
+                        .long("sys")
+                        .takes_value(true)
+                        .default_value("/sys"),
+                ).arg(
+                    Arg::with_name("fps")
+                        .long("fps")
+                        .takes_value(true)
+                        .default_value("10"),
+                ).arg(
+                    Arg::with_name("duration")
+                        .long("duration")
+                        .takes_value(true)
+                        .validator(valid_seconds)
+                        .help("seconds to run, forever if omitted"),
+                ),
+        ).subcommand(
+            SubCommand::with_name("react")
+                .about("light up keys as they are pressed")
+                .arg(
+                    Arg::with_name("reaction")
+                        .required(true)
+                        .possible_values(&["ripple", "afterglow", "heatmap"]),
+                ).arg(
+                    Arg::with_name("device")
+                        .long("device")
+                        .takes_value(true)
+                        .required_unless("replay")
+                        .conflicts_with("replay")
+                        .help("evdev keyboard, e.g. /dev/input/event3"),
+                ).arg(
+                    Arg::with_name("replay")
+                        .long("replay")
+                        .takes_value(true)
+                        .help("recorded evdev events, e.g. from cat /dev/input/event3 > keys.evdev"),
+                ).arg(
+                    Arg::with_name("color")
+                        .long("color")
+                        .takes_value(true)
+                        .validator(valid_color)
+                        .default_value("0,128,255"),
+                ).arg(
+                    Arg::with_name("ramp")
+                        .long("ramp")
+                        .takes_value(true)
+                        .default_value("0,0,0 0,0,255 255,0,0")
+                        .help("heatmap colors from cold to hot"),
+                ).arg(
+                    Arg::with_name("fade")
+                        .long("fade")
+                        .takes_value(true)
+                        .validator(valid_seconds)
+                        .default_value("1")
+                        .help("seconds until a key fades out, half-life of the heatmap"),
+                ).arg(
+                    Arg::with_name("fps")
+                        .long("fps")
+                        .takes_value(true)
+                        .default_value("30"),
+                ).arg(
+                    Arg::with_name("duration")
+                        .long("duration")
+                        .takes_value(true)
+                        .validator(valid_seconds)
+                        .help("seconds to run, forever (or until the replay ends) if omitted"),
+                ),
+        ).subcommand(
+            SubCommand::with_name("marquee")
+                .about("scroll text across all devices supporting custom frames")
+                .arg(Arg::with_name("text").required(true))
+                .arg(
+                    Arg::with_name("color")
+                        .long("color")
+                        .takes_value(true)
+                        .validator(valid_color)
+                        .default_value("255,255,255"),
+                ).arg(
+                    Arg::with_name("background")
+                        .long("background")
+                        .takes_value(true)
+                        .validator(valid_color)
+                        .default_value("0,0,0"),
+                ).arg(
+                    Arg::with_name("speed")
+                        .long("speed")
+                        .takes_value(true)
+                        .default_value("8")
+                        .help("columns per second"),
+                ).arg(
+                    Arg::with_name("once")
+                        .long("once")
+                        .conflicts_with("duration")
+                        .help("stop after the text has scrolled by once"),
+                ).arg(
+                    Arg::with_name("fps")
+                        .long("fps")
+                        .takes_value(true)
+                        .default_value("30"),
+                ).arg(
+                    Arg::with_name("duration")
+                        .long("duration")
+                        .takes_value(true)
+                        .validator(valid_seconds)
+                        .help("seconds to run, forever if omitted"),
+                ),
+        ).subcommand(
+            SubCommand::with_name("timer")
+                .about("count down on the F-row or number row and flash all devices when done")
+                .arg(
+                    Arg::with_name("length")
+                        .required(true)
+                        .validator(valid_timer_length)
+                        .help("e.g. 90, 90s, 25m or 1h30m"),
+                ).arg(
+                    Arg::with_name("break")
+                        .long("break")
+                        .takes_value(true)
+                        .validator(valid_timer_length)
+                        .help("break between cycles, e.g. 5m for a pomodoro timer"),
+                ).arg(
+                    Arg::with_name("cycles")
+                        .long("cycles")
+                        .takes_value(true)
+                        .validator(valid_count)
+                        .default_value("1"),
+                ).arg(
+                    Arg::with_name("row")
+                        .long("row")
+                        .takes_value(true)
+                        .possible_values(&["function", "number"])
+                        .default_value("function"),
+                ).arg(
+                    Arg::with_name("color")
+                        .long("color")
+                        .takes_value(true)
+                        .validator(valid_color)
+                        .default_value("255,64,0"),
+                ).arg(
+                    Arg::with_name("break-color")
+                        .long("break-color")
+                        .takes_value(true)
+                        .validator(valid_color)
+                        .default_value("0,255,64"),
+                ).arg(
+                    Arg::with_name("empty")
+                        .long("empty")
+                        .takes_value(true)
+                        .validator(valid_color)
+                        .default_value("0,0,0"),
+                ).arg(
+                    Arg::with_name("flash-color")
+                        .long("flash-color")
+                        .takes_value(true)
+                        .validator(valid_color)
+                        .default_value("255,255,255"),
+                ).arg(
+                    Arg::with_name("flashes")
+                        .long("flashes")
+                        .takes_value(true)
+                        .validator(valid_count)
+                        .default_value("3"),
+                ).arg(
+                    Arg::with_name("flash-rate")
+                        .long("flash-rate")
+                        .takes_value(true)
+                        .validator(valid_rate)
+                        .default_value("2")
+                        .help("flashes per second"),
+                ).arg(
+                    Arg::with_name("fps")
+                        .long("fps")
+                        .takes_value(true)
+                        .default_value("10"),
+                ),
+        ).subcommand(
+            SubCommand::with_name("clock")
+                .about("show the hour on the F-row and the time digit by digit on the numpad")
+                .arg(
+                    Arg::with_name("hour-color")
+                        .long("hour-color")
+                        .takes_value(true)
+                        .validator(valid_color)
+                        .default_value("255,128,0"),
+                ).arg(
+                    Arg::with_name("minute-color")
+                        .long("minute-color")
+                        .takes_value(true)
+                        .validator(valid_color)
+                        .default_value("0,128,255"),
+                ).arg(
+                    Arg::with_name("background")
+                        .long("background")
+                        .takes_value(true)
+                        .validator(valid_color)
+                        .default_value("0,0,0"),
+                ).arg(
+                    Arg::with_name("fps")
+                        .long("fps")
+                        .takes_value(true)
+                        .default_value("10"),
+                ).arg(
+                    Arg::with_name("duration")
+                        .long("duration")
+                        .takes_value(true)
+                        .validator(valid_seconds)
+                        .help("seconds to run, forever if omitted"),
+                ),
+        ).subcommand(
+            SubCommand::with_name("meter")
+                .about("show numbers read line by line from stdin as a bar, e.g. build progress")
+                .arg(
+                    Arg::with_name("min")
+                        .long("min")
+                        .takes_value(true)
+                        .default_value("0"),
+                ).arg(
+                    Arg::with_name("max")
+                        .long("max")
+                        .takes_value(true)
+                        .default_value("100"),
+                ).arg(
+                    Arg::with_name("zone")
+                        .long("zone")
+                        .takes_value(true)
+                        .default_value("all")
+                        .help("all, row:N, col:N or keys:name,name,..."),
+                ).arg(
+                    Arg::with_name("thresholds")
+                        .long("thresholds")
+                        .takes_value(true)
+                        .default_value("0,255,0")
+                        .help("bar color from a value on, e.g. \"0:255,0,0 50:255,255,0 90:0,255,0\""),
+                ).arg(
+                    Arg::with_name("empty")
+                        .long("empty")
+                        .takes_value(true)
+                        .validator(valid_color)
+                        .default_value("0,0,0"),
+                ).arg(
+                    Arg::with_name("fps")
+                        .long("fps")
+                        .takes_value(true)
+                        .default_value("10"),
+                ),
+        ).subcommand(
+            SubCommand::with_name("heatmap")
+                .about("color keys by how often they are used")
+                .arg(persist_arg())
+                .arg(
+                    Arg::with_name("file")
+                        .required(true)
+                        .help("recorded evdev events, or a .csv file of key code,count lines"),
+                ).arg(
+                    Arg::with_name("ramp")
+                        .long("ramp")
+                        .takes_value(true)
+                        .default_value("0,0,64 0,255,0 255,255,0 255,0,0")
+                        .help("colors from unused to most used"),
+                ).arg(
+                    Arg::with_name("log")
+                        .long("log")
+                        .help("logarithmic scale"),
+                ),
+        ).subcommand(
+            SubCommand::with_name("render")
+                .about("render an effect to an animated GIF or a PNG sprite sheet without hardware")
+                .arg(
+                    Arg::with_name("effect")
+                        .required(true)
+                        .help("effect name or .rhai script"),
+                ).arg(
+                    Arg::with_name("output")
+                        .required(true)
+                        .help(".gif or .png file"),
+                ).arg(
+                    Arg::with_name("param")
+                        .short("p")
+                        .long("param")
+                        .takes_value(true)
+                        .multiple(true)
+                        .number_of_values(1)
+                        .help("effect parameter as name=value"),
+                ).arg(
+                    Arg::with_name("device")
+                        .long("device")
+                        .takes_value(true)
+                        .required_unless("geometry")
+                        .help("layout of a known device, part of its name"),
+                ).arg(
+                    Arg::with_name("geometry")
+                        .long("geometry")
+                        .takes_value(true)
+                        .conflicts_with("device")
+                        .help("rows by columns, e.g. 6x22"),
+                ).arg(
+                    Arg::with_name("fps")
+                        .long("fps")
+                        .takes_value(true)
+                        .default_value("30"),
+                ).arg(
+                    Arg::with_name("duration")
+                        .long("duration")
+                        .takes_value(true)
+                        .validator(valid_seconds)
+                        .default_value("3")
+                        .help("seconds to render"),
+                ).arg(
+                    Arg::with_name("scale")
+                        .long("scale")
+                        .takes_value(true)
+                        .default_value("16")
+                        .help("pixels per LED"),
+                ).arg(
+                    Arg::with_name("columns")
+                        .long("columns")
+                        .takes_value(true)
+                        .default_value("8")
+                        .help("frames per row of a sprite sheet"),
+                ),
+        ).subcommand(
+            SubCommand::with_name("calibrate")
+                .about("guided color calibration of a device, the profile is applied to all colors sent to it")
+                .arg(
+                    Arg::with_name("device")
+                        .required(true)
+                        .help("(part of) the device name"),
+                ).arg(
+                    Arg::with_name("serial")
+                        .long("serial")
+                        .help("calibrate this very device instead of its model"),
+                ).arg(
+                    Arg::with_name("save")
+                        .long("save")
+                        .help("append the profile to the calibration file instead of printing it"),
+                ),
         ).get_matches();
 
     let mut log_builder = env_logger::Builder::from_default_env();
@@ -42,15 +736,192 @@ fn main() {
     }
     log_builder.init();
 
+    let mut preview = Vec::new();
+    for pattern in matches.values_of("preview").into_iter().flatten() {
+        preview.extend(devices::find_layouts(pattern).unwrap());
+    }
+
     if let Some(_) = matches.subcommand_matches("list") {
         cli::list_devices().unwrap();
     } else if let Some(_) = matches.subcommand_matches("get-brightness") {
         cli::get_brightness().unwrap();
     } else if let Some(sub_matches) = matches.subcommand_matches("set-brightness") {
-        let brightness = sub_matches.value_of("brightness").unwrap().parse::<u8>().unwrap();
-        cli::set_brightness(brightness).unwrap();
+        let brightness = sub_matches.value_of("brightness").unwrap().parse::<Brightness>().unwrap();
+        let zone = sub_matches.value_of("zone").map(|zone| zone.parse::<LedZone>().unwrap());
+        if let Some(fade) = seconds(sub_matches, "fade") {
+            let easing = sub_matches.value_of("easing").unwrap().parse::<Easing>().unwrap();
+            let transition = Transition::new(fade, easing);
+            let from = sub_matches.value_of("from").map(|from| from.parse::<Brightness>().unwrap());
+            cli::fade_brightness(from, brightness, zone, transition, persistence(sub_matches)).unwrap();
+        } else {
+            cli::set_brightness(brightness, zone, persistence(sub_matches)).unwrap();
+        }
     } else if let Some(sub_matches) = matches.subcommand_matches("set-color") {
         let color = Color::parse(sub_matches.value_of("color").unwrap()).unwrap();
-        cli::set_color(color).unwrap();
+        if let Some(fade) = seconds(sub_matches, "fade") {
+            let easing = sub_matches.value_of("easing").unwrap().parse::<Easing>().unwrap();
+            let transition = Transition::new(fade, easing);
+            let from = sub_matches.value_of("from").map(|from| Color::parse(from).unwrap());
+            let fps = sub_matches.value_of("fps").unwrap().parse::<u32>().unwrap();
+            let persistence = persistence(sub_matches);
+            cli::fade_color(from.unwrap_or_default(), color, transition, fps, persistence, &preview).unwrap();
+        } else {
+            cli::set_color(color, persistence(sub_matches)).unwrap();
+        }
+    } else if let Some(sub_matches) = matches.subcommand_matches("set-gradient") {
+        let stops = sub_matches
+            .values_of("colors")
+            .unwrap()
+            .map(|color| Color::parse(color).unwrap())
+            .collect();
+        let direction = sub_matches.value_of("direction").unwrap().parse::<Direction>().unwrap();
+        let interpolation = sub_matches.value_of("interpolation").unwrap().parse::<Interpolation>().unwrap();
+        cli::set_gradient(&Gradient::new(stops, direction, interpolation), persistence(sub_matches), &preview).unwrap();
+    } else if let Some(sub_matches) = matches.subcommand_matches("theme") {
+        let theme = Theme::find(sub_matches.value_of("theme").unwrap()).unwrap();
+        cli::apply_theme(&theme, persistence(sub_matches), &preview).unwrap();
+    } else if let Some(_) = matches.subcommand_matches("effects") {
+        cli::list_effects().unwrap();
+    } else if let Some(sub_matches) = matches.subcommand_matches("animate") {
+        let params = EffectParams::parse(sub_matches.values_of("param").into_iter().flatten()).unwrap();
+        let fps = sub_matches.value_of("fps").unwrap().parse::<u32>().unwrap();
+        let duration = seconds(sub_matches, "duration");
+        let spread = if sub_matches.is_present("desk") {
+            cli::Spread::Desk
+        } else if let Some(mirror) = sub_matches.value_of("mirror") {
+            let sampling = sub_matches.value_of("sampling").unwrap().parse::<Sampling>().unwrap();
+            cli::Spread::Mirror(devices::find_layouts(mirror).unwrap()[0], sampling)
+        } else {
+            cli::Spread::Device
+        };
+        cli::animate(sub_matches.value_of("effect").unwrap(), &params, fps, duration, &spread, &preview).unwrap();
+    } else if let Some(sub_matches) = matches.subcommand_matches("script") {
+        let fps = sub_matches.value_of("fps").unwrap().parse::<u32>().unwrap();
+        let duration = seconds(sub_matches, "duration");
+        cli::run_script(sub_matches.value_of("file").unwrap(), fps, duration, &preview).unwrap();
+    } else if let Some(sub_matches) = matches.subcommand_matches("play") {
+        let fps = sub_matches.value_of("fps").unwrap().parse::<u32>().unwrap();
+        let duration = seconds(sub_matches, "duration");
+        cli::play(sub_matches.value_of("file").unwrap(), fps, duration, &preview).unwrap();
+    } else if let Some(sub_matches) = matches.subcommand_matches("compose") {
+        let fps = sub_matches.value_of("fps").unwrap().parse::<u32>().unwrap();
+        let duration = seconds(sub_matches, "duration");
+        cli::compose(sub_matches.value_of("file").unwrap(), fps, duration, &preview).unwrap();
+    } else if let Some(sub_matches) = matches.subcommand_matches("visualize") {
+        let raw_format = PcmFormat {
+            sample_rate: sub_matches.value_of("rate").unwrap().parse::<u32>().unwrap(),
+            channels: sub_matches.value_of("channels").unwrap().parse::<u16>().unwrap(),
+            encoding: sub_matches.value_of("format").unwrap().parse().unwrap(),
+        };
+        let low = Color::parse(sub_matches.value_of("low").unwrap()).unwrap();
+        let high = Color::parse(sub_matches.value_of("high").unwrap()).unwrap();
+        let gain = sub_matches.value_of("gain").unwrap().parse::<f64>().unwrap();
+        let fps = sub_matches.value_of("fps").unwrap().parse::<u32>().unwrap();
+        cli::visualize(sub_matches.value_of("input"), raw_format, low, high, gain, fps, &preview).unwrap();
+    } else if let Some(sub_matches) = matches.subcommand_matches("monitor") {
+        let metric = sub_matches.value_of("metric").unwrap().parse().unwrap();
+        let paths = SystemPaths {
+            proc_root: PathBuf::from(sub_matches.value_of("proc").unwrap()),
+            sys_root: PathBuf::from(sub_matches.value_of("sys").unwrap()),
+        };
+        let options = cli::MonitorOptions {
+            ramp: Ramp::parse(sub_matches.value_of("ramp").unwrap()).unwrap(),
+            range: (
+                sub_matches.value_of("min").unwrap().parse::<f64>().unwrap(),
+                sub_matches.value_of("max").unwrap().parse::<f64>().unwrap(),
+            ),
+            interval: sub_matches.value_of("interval").unwrap().parse::<f64>().unwrap(),
+        };
+        let fps = sub_matches.value_of("fps").unwrap().parse::<u32>().unwrap();
+        let duration = seconds(sub_matches, "duration");
+        cli::monitor(metric, &paths, &options, fps, duration, &preview).unwrap();
+    } else if let Some(sub_matches) = matches.subcommand_matches("react") {
+        let reaction = sub_matches.value_of("reaction").unwrap().parse().unwrap();
+        let input = match sub_matches.value_of("device") {
+            Some(path) => cli::KeyInput::Device(path),
+            None => cli::KeyInput::Replay(sub_matches.value_of("replay").unwrap()),
+        };
+        let options = cli::ReactOptions {
+            color: Color::parse(sub_matches.value_of("color").unwrap()).unwrap(),
+            fade: sub_matches.value_of("fade").unwrap().parse::<f64>().unwrap(),
+            ramp: Ramp::parse(sub_matches.value_of("ramp").unwrap()).unwrap(),
+        };
+        let fps = sub_matches.value_of("fps").unwrap().parse::<u32>().unwrap();
+        let duration = seconds(sub_matches, "duration");
+        cli::react(reaction, &input, &options, fps, duration, &preview).unwrap();
+    } else if let Some(sub_matches) = matches.subcommand_matches("marquee") {
+        let params = MarqueeParams {
+            text: sub_matches.value_of("text").unwrap().to_string(),
+            color: Color::parse(sub_matches.value_of("color").unwrap()).unwrap(),
+            background: Color::parse(sub_matches.value_of("background").unwrap()).unwrap(),
+            speed: sub_matches.value_of("speed").unwrap().parse::<f64>().unwrap(),
+        };
+        let fps = sub_matches.value_of("fps").unwrap().parse::<u32>().unwrap();
+        let duration = seconds(sub_matches, "duration");
+        cli::marquee(&params, sub_matches.is_present("once"), fps, duration, &preview).unwrap();
+    } else if let Some(sub_matches) = matches.subcommand_matches("timer") {
+        let work = Phase {
+            length: timer::parse_duration(sub_matches.value_of("length").unwrap()).unwrap(),
+            color: Color::parse(sub_matches.value_of("color").unwrap()).unwrap(),
+        };
+        let pause = sub_matches.value_of("break").map(|length| Phase {
+            length: timer::parse_duration(length).unwrap(),
+            color: Color::parse(sub_matches.value_of("break-color").unwrap()).unwrap(),
+        });
+        let cycles = sub_matches.value_of("cycles").unwrap().parse::<usize>().unwrap();
+        let mut phases = Vec::new();
+        for cycle in 0..cycles {
+            if cycle > 0 {
+                phases.extend(pause);
+            }
+            phases.push(work);
+        }
+        let options = cli::TimerOptions {
+            row: sub_matches.value_of("row").unwrap().parse().unwrap(),
+            empty: Color::parse(sub_matches.value_of("empty").unwrap()).unwrap(),
+            flash: Flash {
+                color: Color::parse(sub_matches.value_of("flash-color").unwrap()).unwrap(),
+                count: sub_matches.value_of("flashes").unwrap().parse::<u32>().unwrap(),
+                rate: sub_matches.value_of("flash-rate").unwrap().parse::<f64>().unwrap(),
+            },
+        };
+        let fps = sub_matches.value_of("fps").unwrap().parse::<u32>().unwrap();
+        cli::timer(&phases, &options, fps, &preview).unwrap();
+    } else if let Some(sub_matches) = matches.subcommand_matches("clock") {
+        let hour = Color::parse(sub_matches.value_of("hour-color").unwrap()).unwrap();
+        let minute = Color::parse(sub_matches.value_of("minute-color").unwrap()).unwrap();
+        let background = Color::parse(sub_matches.value_of("background").unwrap()).unwrap();
+        let fps = sub_matches.value_of("fps").unwrap().parse::<u32>().unwrap();
+        let duration = seconds(sub_matches, "duration");
+        cli::clock(hour, minute, background, fps, duration, &preview).unwrap();
+    } else if let Some(sub_matches) = matches.subcommand_matches("meter") {
+        let range = (
+            sub_matches.value_of("min").unwrap().parse::<f64>().unwrap(),
+            sub_matches.value_of("max").unwrap().parse::<f64>().unwrap(),
+        );
+        let zone = sub_matches.value_of("zone").unwrap().parse::<Zone>().unwrap();
+        let thresholds = Thresholds::parse(sub_matches.value_of("thresholds").unwrap()).unwrap();
+        let empty = Color::parse(sub_matches.value_of("empty").unwrap()).unwrap();
+        let fps = sub_matches.value_of("fps").unwrap().parse::<u32>().unwrap();
+        cli::meter(range, &zone, &thresholds, empty, fps, &preview).unwrap();
+    } else if let Some(sub_matches) = matches.subcommand_matches("heatmap") {
+        let ramp = Ramp::parse(sub_matches.value_of("ramp").unwrap()).unwrap();
+        let logarithmic = sub_matches.is_present("log");
+        let persistence = persistence(sub_matches);
+        cli::heatmap(sub_matches.value_of("file").unwrap(), &ramp, logarithmic, persistence, &preview).unwrap();
+    } else if let Some(sub_matches) = matches.subcommand_matches("render") {
+        let params = EffectParams::parse(sub_matches.values_of("param").into_iter().flatten()).unwrap();
+        let layout = cli::find_layout(sub_matches.value_of("device"), sub_matches.value_of("geometry")).unwrap();
+        let options = cli::RenderOptions {
+            fps: sub_matches.value_of("fps").unwrap().parse::<u32>().unwrap(),
+            duration: seconds(sub_matches, "duration").unwrap(),
+            scale: sub_matches.value_of("scale").unwrap().parse::<usize>().unwrap(),
+            columns: sub_matches.value_of("columns").unwrap().parse::<usize>().unwrap(),
+        };
+        let effect = sub_matches.value_of("effect").unwrap();
+        cli::render(effect, &params, &layout, sub_matches.value_of("output").unwrap(), &options).unwrap();
+    } else if let Some(sub_matches) = matches.subcommand_matches("calibrate") {
+        let device = sub_matches.value_of("device").unwrap();
+        cli::calibrate(device, sub_matches.is_present("serial"), sub_matches.is_present("save")).unwrap();
     }
 }
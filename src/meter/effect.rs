@@ -0,0 +1,111 @@
+use super::ValueInput;
+use animation::Effect;
+use devices::{split_colors, Color, Frame, Keymap, Zone};
+use errors::{ErrorKind, Result};
+use std::cmp::Reverse;
+
+// Bar colors by value, each color is used from its value up to the next one.
+#[derive(Debug, Clone)]
+pub struct Thresholds {
+    steps: Vec<(f64, Color)>,
+}
+
+impl Thresholds {
+    // e.g. "0:255,0,0 50:255,255,0 90:0,255,0", or just a color.
+    pub fn parse(thresholds_str: &str) -> Result<Thresholds> {
+        let mut steps = Vec::new();
+
+        for step in split_colors(thresholds_str) {
+            let (value, color) = match step.find(':') {
+                Some(pos) => (
+                    step[..pos]
+                        .parse::<f64>()
+                        .ok()
+                        .filter(|value| !value.is_nan())
+                        .ok_or_else(|| ErrorKind::InvalidParameter("thresholds".to_string(), step.to_string()))?,
+                    &step[pos + 1..],
+                ),
+                None => (f64::NEG_INFINITY, step),
+            };
+            steps.push((value, Color::parse(color)?));
+        }
+
+        if steps.is_empty() {
+            return Err(ErrorKind::InvalidParameter("thresholds".to_string(), thresholds_str.to_string()).into());
+        }
+        steps.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        Ok(Thresholds { steps })
+    }
+
+    pub fn color(&self, value: f64) -> Color {
+        self.steps
+            .iter()
+            .rev()
+            .find(|&&(step, _)| value >= step)
+            .unwrap_or(&self.steps[0])
+            .1
+    }
+}
+
+// Shows the latest value as a bar filling the zone, columns go bottom to
+// top and the whole matrix left to right, each column bottom to top.
+pub struct ValueMeter {
+    input: ValueInput,
+    range: (f64, f64),
+    zone: Zone,
+    thresholds: Thresholds,
+    empty: Color,
+    keymap: Option<&'static Keymap>,
+}
+
+impl ValueMeter {
+    pub fn new(
+        input: ValueInput,
+        range: (f64, f64),
+        zone: Zone,
+        thresholds: Thresholds,
+        empty: Color,
+        keymap: Option<&'static Keymap>,
+    ) -> ValueMeter {
+        ValueMeter {
+            input,
+            range,
+            zone,
+            thresholds,
+            empty,
+            keymap,
+        }
+    }
+}
+
+impl Effect for ValueMeter {
+    fn render(&mut self, _t: f64, frame: &mut Frame) {
+        let mut cells = self.zone.cells(frame.geometry(), self.keymap);
+
+        frame.fill(Color::default());
+
+        match self.zone {
+            Zone::All => cells.sort_by_key(|&(row, col)| (col, Reverse(row))),
+            Zone::Column(_) => cells.reverse(),
+            _ => (),
+        }
+
+        let value = match self.input.value() {
+            Some(value) => value,
+            None => {
+                for &(row, col) in cells.iter() {
+                    frame.set(row, col, self.empty);
+                }
+                return;
+            }
+        };
+        let (min, max) = self.range;
+        let fraction = ((value - min) / (max - min)).clamp(0.0, 1.0);
+        let lit = fraction * cells.len() as f64;
+        let color = self.thresholds.color(value);
+
+        for (i, &(row, col)) in cells.iter().enumerate() {
+            frame.set(row, col, self.empty.mix(color, lit - i as f64));
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use std::io::BufRead;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+struct ValueState {
+    value: Option<f64>,
+    closed: bool,
+}
+
+// The latest number read line by line by a background thread, lines that are
+// not a number (an optional trailing '%' is fine) are skipped.
+#[derive(Clone)]
+pub struct ValueInput {
+    state: Arc<Mutex<ValueState>>,
+}
+
+impl ValueInput {
+    pub fn read<R: BufRead + Send + 'static>(reader: R) -> ValueInput {
+        let state = Arc::new(Mutex::new(ValueState {
+            value: None,
+            closed: false,
+        }));
+        let thread_state = state.clone();
+
+        thread::spawn(move || {
+            for line in reader.lines() {
+                let line = match line {
+                    Ok(line) => line,
+                    Err(error) => {
+                        warn!("Reading values failed: {}", error);
+                        break;
+                    }
+                };
+                match line.trim().trim_end_matches('%').trim().parse::<f64>() {
+                    Ok(value) if value.is_finite() => thread_state.lock().unwrap().value = Some(value),
+                    _ => debug!("Skipping '{}'", line),
+                }
+            }
+            thread_state.lock().unwrap().closed = true;
+        });
+
+        ValueInput { state }
+    }
+
+    pub fn value(&self) -> Option<f64> {
+        self.state.lock().unwrap().value
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.state.lock().unwrap().closed
+    }
+}
@@ -0,0 +1,5 @@
+mod effect;
+mod input;
+
+pub use self::effect::*;
+pub use self::input::*;
@@ -0,0 +1,54 @@
+use animation::{Animation, Effect, ManualClock, MemorySink};
+use devices::{Frame, Geometry};
+use errors::Result;
+use std::time::Duration;
+
+// Renders `duration` of an effect on the deterministic clock, without any hardware.
+pub fn render_frames(effect: Box<Effect>, geometry: Geometry, fps: u32, duration: Duration) -> Result<Vec<Frame>> {
+    let clock = ManualClock::new();
+    let sink = MemorySink::new(geometry, clock.clone());
+    let mut animation = Animation::new(clock);
+
+    animation.add_track(Box::new(sink.clone()), effect, fps)?;
+    animation.run_for(duration);
+
+    // Frame times are rounded to nanoseconds, which can fit one more frame in.
+    let count = (duration.as_secs_f64() * fps as f64).round() as usize;
+    Ok(sink.frames().into_iter().take(count).map(|(_, frame)| frame).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use devices::Color;
+    use effects::{self, EffectParams};
+
+    fn render(fps: u32, seconds: u64) -> Vec<Frame> {
+        let params = EffectParams::parse(vec!["length=4", "speed=0.25"]).unwrap();
+        let effect = effects::find_effect("gradient").unwrap().create(&params).unwrap();
+
+        render_frames(effect, Geometry::new(1, 4), fps, Duration::from_secs(seconds)).unwrap()
+    }
+
+    #[test]
+    fn renders_a_frame_per_tick() {
+        assert_eq!(render(4, 2).len(), 8);
+        assert_eq!(render(30, 1).len(), 30);
+        assert_eq!(render(4, 0).len(), 0);
+    }
+
+    #[test]
+    fn renders_the_same_frames_every_time() {
+        let frames = render(4, 2);
+        let (red, blue) = (Color::new(255, 0, 0), Color::new(0, 0, 255));
+        let purple = red.mix(blue, 0.5);
+
+        assert_eq!(frames, render(4, 2));
+        assert_eq!(frames[0].row(0), &[red, purple, blue, purple][..]);
+        // A quarter of the gradient, one column, scrolls by every second.
+        for col in 0..3 {
+            assert_eq!(frames[4].get(0, col + 1), frames[0].get(0, col));
+        }
+        assert_eq!(frames[4].get(0, 0), purple);
+    }
+}
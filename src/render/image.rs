@@ -0,0 +1,139 @@
+use devices::{Frame, Keymap};
+use errors::{ErrorKind, Result};
+use gif;
+use png;
+use std::convert::TryFrom;
+use std::io::Write;
+
+const BACKGROUND: [u8; 3] = [24, 24, 24];
+
+// Pixel layout of a frame, every LED a `scale` sized square with a one pixel gap.
+#[derive(Debug, Clone, Copy)]
+pub struct ImageStyle {
+    pub scale: usize,
+    pub keymap: Option<&'static Keymap>,
+}
+
+impl ImageStyle {
+    fn size(&self, frame: &Frame) -> (usize, usize) {
+        (frame.cols() * self.scale, frame.rows() * self.scale)
+    }
+
+    // Draws the frame into an RGB buffer `stride` pixels wide at (x, y).
+    fn draw(&self, frame: &Frame, buffer: &mut [u8], stride: usize, x: usize, y: usize) {
+        let (width, height) = self.size(frame);
+
+        for py in 0..height {
+            for px in 0..width {
+                let (row, col) = (py / self.scale, px / self.scale);
+                let edge = py % self.scale == self.scale - 1 || px % self.scale == self.scale - 1;
+                let is_key = self
+                    .keymap
+                    .map(|keymap| keymap.keys().iter().any(|key| key.row == row && key.col == col))
+                    .unwrap_or(true);
+                let color = frame.get(row, col);
+                let rgb = if edge || !is_key {
+                    BACKGROUND
+                } else {
+                    [color.red, color.green, color.blue]
+                };
+                let offset = ((y + py) * stride + x + px) * 3;
+
+                buffer[offset..offset + 3].copy_from_slice(&rgb);
+            }
+        }
+    }
+}
+
+pub fn write_gif<W: Write>(writer: W, frames: &[Frame], fps: u32, style: &ImageStyle) -> Result<()> {
+    let (width, height) = match frames.first() {
+        Some(frame) => style.size(frame),
+        None => return Ok(()),
+    };
+    // GIF sizes are 16 bit.
+    let too_large = || ErrorKind::InvalidParameter("scale".to_string(), style.scale.to_string());
+    let gif_width = u16::try_from(width).map_err(|_| too_large())?;
+    let gif_height = u16::try_from(height).map_err(|_| too_large())?;
+    let mut encoder = gif::Encoder::new(writer, gif_width, gif_height, &[])?;
+    let mut buffer = vec![0u8; width * height * 3];
+    // GIF delays are in hundredths of a second.
+    let delay = (100.0 / fps.max(1) as f64).round().max(1.0) as u16;
+
+    encoder.set_repeat(gif::Repeat::Infinite)?;
+    for frame in frames {
+        style.draw(frame, &mut buffer, width, 0, 0);
+        let mut gif_frame = gif::Frame::from_rgb_speed(gif_width, gif_height, &buffer, 10);
+        gif_frame.delay = delay;
+        encoder.write_frame(&gif_frame)?;
+    }
+
+    Ok(())
+}
+
+// All frames on one image, `columns` frames per row, left to right and top to bottom.
+pub fn write_sprite_sheet<W: Write>(writer: W, frames: &[Frame], columns: usize, style: &ImageStyle) -> Result<()> {
+    let (width, height) = match frames.first() {
+        Some(frame) => style.size(frame),
+        None => return Ok(()),
+    };
+    let columns = columns.clamp(1, frames.len());
+    let rows = frames.len().div_ceil(columns);
+    // PNG sizes are 32 bit, and the buffer has to fit in memory.
+    let too_large = || ErrorKind::InvalidParameter("scale".to_string(), style.scale.to_string());
+    let stride = width.checked_mul(columns).ok_or_else(too_large)?;
+    let sheet_height = height.checked_mul(rows).ok_or_else(too_large)?;
+    let size = stride
+        .checked_mul(sheet_height)
+        .and_then(|pixels| pixels.checked_mul(3))
+        .ok_or_else(too_large)?;
+    let png_width = u32::try_from(stride).map_err(|_| too_large())?;
+    let png_height = u32::try_from(sheet_height).map_err(|_| too_large())?;
+    let mut buffer = vec![0u8; size];
+
+    for pixel in buffer.chunks_mut(3) {
+        pixel.copy_from_slice(&BACKGROUND);
+    }
+    for (i, frame) in frames.iter().enumerate() {
+        style.draw(frame, &mut buffer, stride, (i % columns) * width, (i / columns) * height);
+    }
+
+    let mut encoder = png::Encoder::new(writer, png_width, png_height);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.write_header()?.write_image_data(&buffer)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use devices::Geometry;
+    use png;
+
+    fn frames(count: usize) -> Vec<Frame> {
+        vec![Frame::new(Geometry::new(2, 4)); count]
+    }
+
+    #[test]
+    fn lays_out_sprite_sheets() {
+        let style = ImageStyle { scale: 3, keymap: None };
+        let mut png_data = Vec::new();
+        write_sprite_sheet(&mut png_data, &frames(5), 2, &style).unwrap();
+
+        let reader = png::Decoder::new(&png_data[..]).read_info().unwrap();
+        assert_eq!((reader.info().width, reader.info().height), (2 * 4 * 3, 3 * 2 * 3));
+    }
+
+    #[test]
+    fn rejects_sprite_sheets_too_large() {
+        // Every side fits, but not the whole sheet.
+        let style = ImageStyle { scale: usize::MAX / 8, keymap: None };
+        let error = write_sprite_sheet(Vec::new(), &frames(3), 1, &style).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            ErrorKind::InvalidParameter("scale".to_string(), (usize::MAX / 8).to_string()).to_string()
+        );
+    }
+}
@@ -0,0 +1,5 @@
+mod frames;
+mod image;
+
+pub use self::frames::*;
+pub use self::image::*;
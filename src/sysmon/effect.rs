@@ -0,0 +1,92 @@
+use super::sources::{memory_pressure, memory_usage, temperatures, CpuLoad, Metric, SystemPaths};
+use animation::Effect;
+use devices::{Color, Frame};
+use effects::palette::Ramp;
+use errors::Result;
+
+pub struct SystemMonitor {
+    metric: Metric,
+    paths: SystemPaths,
+    cpu: CpuLoad,
+    ramp: Ramp,
+    // Temperatures in this range are mapped onto the ramp.
+    range: (f64, f64),
+    interval: f64,
+    next_sample: f64,
+    values: Vec<f64>,
+}
+
+impl SystemMonitor {
+    pub fn new(metric: Metric, paths: SystemPaths, ramp: Ramp, range: (f64, f64), interval: f64) -> SystemMonitor {
+        SystemMonitor {
+            metric,
+            cpu: CpuLoad::new(paths.clone()),
+            paths,
+            ramp,
+            range,
+            interval,
+            next_sample: 0.0,
+            values: Vec::new(),
+        }
+    }
+
+    // Normalized values in [0, 1] of the metric.
+    pub fn sample(&mut self) -> Result<Vec<f64>> {
+        match self.metric {
+            Metric::Cpu => self.cpu.sample(),
+            Metric::Memory => Ok(vec![memory_usage(&self.paths)?]),
+            Metric::Pressure => Ok(vec![memory_pressure(&self.paths)?]),
+            Metric::Temperature => {
+                let (low, high) = self.range;
+
+                Ok(temperatures(&self.paths)?
+                    .into_iter()
+                    .map(|t| ((t - low) / (high - low)).clamp(0.0, 1.0))
+                    .collect())
+            }
+        }
+    }
+}
+
+impl Effect for SystemMonitor {
+    fn render(&mut self, t: f64, frame: &mut Frame) {
+        if t >= self.next_sample {
+            self.next_sample = t + self.interval;
+            match self.sample() {
+                Ok(values) => self.values = values,
+                Err(error) => warn!("Reading {:?} failed: {}", self.metric, error),
+            }
+        }
+        let (rows, cols) = (frame.rows(), frame.cols());
+
+        frame.fill(Color::default());
+        if self.values.is_empty() {
+            return;
+        }
+        if rows > 1 && self.values.len() <= rows {
+            // One horizontal bar per value.
+            for (row, value) in self.values.iter().enumerate() {
+                draw_bar(frame, row, *value, &self.ramp);
+            }
+        } else if rows > 1 {
+            // Too many values for bars, each value lights one cell instead.
+            for (index, value) in self.values.iter().take(rows * cols).enumerate() {
+                frame.set(index / cols, index % cols, self.ramp.sample(*value));
+            }
+        } else {
+            let mean = self.values.iter().sum::<f64>() / self.values.len() as f64;
+
+            draw_bar(frame, 0, mean, &self.ramp);
+        }
+    }
+}
+
+fn draw_bar(frame: &mut Frame, row: usize, value: f64, ramp: &Ramp) {
+    let cols = frame.cols();
+    let lit = value * cols as f64;
+    let color = ramp.sample(value);
+
+    for col in 0..cols {
+        frame.set(row, col, color.scale((lit - col as f64).clamp(0.0, 1.0)));
+    }
+}
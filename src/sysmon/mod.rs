@@ -0,0 +1,5 @@
+mod effect;
+mod sources;
+
+pub use self::effect::*;
+pub use self::sources::*;
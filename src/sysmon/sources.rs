@@ -0,0 +1,282 @@
+use errors::{ErrorKind, Result, ResultExt};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+// Roots of the proc and sys trees, can be pointed to a fake tree for testing.
+#[derive(Debug, Clone)]
+pub struct SystemPaths {
+    pub proc_root: PathBuf,
+    pub sys_root: PathBuf,
+}
+
+impl Default for SystemPaths {
+    fn default() -> SystemPaths {
+        SystemPaths {
+            proc_root: PathBuf::from("/proc"),
+            sys_root: PathBuf::from("/sys"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Metric {
+    Cpu,
+    Memory,
+    Pressure,
+    Temperature,
+}
+
+impl FromStr for Metric {
+    type Err = ::errors::Error;
+
+    fn from_str(s: &str) -> Result<Metric> {
+        match s {
+            "cpu" => Ok(Metric::Cpu),
+            "memory" => Ok(Metric::Memory),
+            "pressure" => Ok(Metric::Pressure),
+            "temperature" => Ok(Metric::Temperature),
+            _ => Err(ErrorKind::InvalidParameter("metric".to_string(), s.to_string()).into()),
+        }
+    }
+}
+
+fn invalid(path: &Path) -> ErrorKind {
+    ErrorKind::InvalidSystemInfo(path.display().to_string())
+}
+
+// Load of each core in [0, 1] since the previous sample.
+pub struct CpuLoad {
+    paths: SystemPaths,
+    last: Vec<(u64, u64)>,
+}
+
+impl CpuLoad {
+    pub fn new(paths: SystemPaths) -> CpuLoad {
+        CpuLoad {
+            paths,
+            last: Vec::new(),
+        }
+    }
+
+    fn read_times(&self) -> Result<Vec<(u64, u64)>> {
+        let path = self.paths.proc_root.join("stat");
+        let content = fs::read_to_string(&path).chain_err(|| invalid(&path))?;
+        let mut times = Vec::new();
+
+        // Per core lines look like "cpu0 user nice system idle iowait irq softirq steal ..."
+        for line in content.lines().filter(|line| line.starts_with("cpu") && !line.starts_with("cpu ")) {
+            let fields = line
+                .split_whitespace()
+                .skip(1)
+                .take(8)
+                .map(|field| field.parse::<u64>().map_err(|_| invalid(&path).into()))
+                .collect::<Result<Vec<u64>>>()?;
+            if fields.len() < 4 {
+                return Err(invalid(&path).into());
+            }
+            let total: u64 = fields.iter().sum();
+            let idle = fields[3] + fields.get(4).cloned().unwrap_or(0);
+
+            times.push((total - idle, total));
+        }
+
+        Ok(times)
+    }
+
+    pub fn sample(&mut self) -> Result<Vec<f64>> {
+        let times = self.read_times()?;
+        let loads = times
+            .iter()
+            .enumerate()
+            .map(|(core, &(busy, total))| {
+                let (last_busy, last_total) = self.last.get(core).cloned().unwrap_or((0, 0));
+
+                if total > last_total {
+                    busy.saturating_sub(last_busy) as f64 / (total - last_total) as f64
+                } else {
+                    0.0
+                }
+            })
+            .collect();
+        self.last = times;
+
+        Ok(loads)
+    }
+}
+
+// Fraction of memory in use, i.e. not available to new allocations.
+pub fn memory_usage(paths: &SystemPaths) -> Result<f64> {
+    let path = paths.proc_root.join("meminfo");
+    let content = fs::read_to_string(&path).chain_err(|| invalid(&path))?;
+    let field = |name: &str| {
+        content
+            .lines()
+            .find(|line| line.starts_with(name))
+            .and_then(|line| line.split_whitespace().nth(1))
+            .and_then(|value| value.parse::<f64>().ok())
+    };
+
+    match (field("MemTotal:"), field("MemAvailable:")) {
+        (Some(total), Some(available)) if total > 0.0 => Ok(((total - available) / total).clamp(0.0, 1.0)),
+        _ => Err(invalid(&path).into()),
+    }
+}
+
+// Share of the last 10 seconds some task was stalled waiting for memory,
+// from pressure stall information (PSI).
+pub fn memory_pressure(paths: &SystemPaths) -> Result<f64> {
+    let path = paths.proc_root.join("pressure/memory");
+    let content = fs::read_to_string(&path).chain_err(|| invalid(&path))?;
+
+    // "some avg10=1.23 avg60=0.50 avg300=0.10 total=12345"
+    content
+        .lines()
+        .find(|line| line.starts_with("some "))
+        .and_then(|line| line.split_whitespace().find(|field| field.starts_with("avg10=")))
+        .and_then(|field| field["avg10=".len()..].parse::<f64>().ok())
+        .map(|percent| (percent / 100.0).clamp(0.0, 1.0))
+        .ok_or_else(|| invalid(&path).into())
+}
+
+// All temperature sensors found in the hwmon class, in degree Celsius.
+pub fn temperatures(paths: &SystemPaths) -> Result<Vec<f64>> {
+    let hwmon = paths.sys_root.join("class/hwmon");
+    let mut sensors = Vec::new();
+
+    for device in fs::read_dir(&hwmon).chain_err(|| invalid(&hwmon))? {
+        let device = device?.path();
+
+        for entry in fs::read_dir(&device)? {
+            let path = entry?.path();
+            let is_temp = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .map(|name| name.starts_with("temp") && name.ends_with("_input"))
+                .unwrap_or(false);
+
+            if is_temp {
+                sensors.push(path);
+            }
+        }
+    }
+    sensors.sort();
+
+    let mut temperatures = Vec::new();
+    for path in sensors {
+        // Sensors that are not readable at the moment are skipped.
+        if let Some(millis) = fs::read_to_string(&path).ok().and_then(|v| v.trim().parse::<f64>().ok()) {
+            temperatures.push(millis / 1000.0);
+        }
+    }
+
+    Ok(temperatures)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::process;
+
+    // A fake proc and sys tree in a temporary directory, removed on drop.
+    struct FakeTree {
+        root: PathBuf,
+    }
+
+    impl FakeTree {
+        fn new(name: &str) -> FakeTree {
+            let root = env::temp_dir().join(format!("razer_test_sysmon_{}_{}", name, process::id()));
+            let _ = fs::remove_dir_all(&root);
+
+            FakeTree { root }
+        }
+
+        fn write(&self, path: &str, content: &str) {
+            let path = self.root.join(path);
+
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+
+        fn paths(&self) -> SystemPaths {
+            SystemPaths {
+                proc_root: self.root.join("proc"),
+                sys_root: self.root.join("sys"),
+            }
+        }
+    }
+
+    impl Drop for FakeTree {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.root);
+        }
+    }
+
+    #[test]
+    fn samples_cpu_load_per_core() {
+        let tree = FakeTree::new("cpu");
+        let mut cpu = CpuLoad::new(tree.paths());
+
+        tree.write(
+            "proc/stat",
+            "cpu  200 0 100 700 0 0 0 0 0 0\ncpu0 100 0 50 350 0 0 0 0 0 0\ncpu1 100 0 50 350 0 0 0 0 0 0\nintr 1\n",
+        );
+        assert_eq!(cpu.sample().unwrap(), vec![0.3, 0.3]);
+
+        // Core 0 busy for 75 of 100 ticks, core 1 idle (and waiting on IO).
+        tree.write(
+            "proc/stat",
+            "cpu  275 0 100 775 25 0 0 0 0 0\ncpu0 150 0 75 375 0 0 0 0 0 0\ncpu1 100 0 50 425 25 0 0 0 0 0\n",
+        );
+        assert_eq!(cpu.sample().unwrap(), vec![0.75, 0.0]);
+    }
+
+    #[test]
+    fn rejects_malformed_stat() {
+        let tree = FakeTree::new("stat");
+
+        tree.write("proc/stat", "cpu0 1 2 x\n");
+        assert!(CpuLoad::new(tree.paths()).sample().is_err());
+    }
+
+    #[test]
+    fn reads_memory_usage_and_pressure() {
+        let tree = FakeTree::new("memory");
+
+        tree.write(
+            "proc/meminfo",
+            "MemTotal:       16000000 kB\nMemFree:         1000000 kB\nMemAvailable:    4000000 kB\n",
+        );
+        tree.write(
+            "proc/pressure/memory",
+            "some avg10=12.50 avg60=3.00 avg300=1.00 total=123\nfull avg10=5.00 avg60=1.00 avg300=0.50 total=45\n",
+        );
+
+        assert_eq!(memory_usage(&tree.paths()).unwrap(), 0.75);
+        assert_eq!(memory_pressure(&tree.paths()).unwrap(), 0.125);
+    }
+
+    #[test]
+    fn fails_without_pressure_information() {
+        let tree = FakeTree::new("no_psi");
+
+        tree.write("proc/meminfo", "MemTotal: 100 kB\n");
+        assert!(memory_usage(&tree.paths()).is_err());
+        assert!(memory_pressure(&tree.paths()).is_err());
+    }
+
+    #[test]
+    fn reads_hwmon_temperatures() {
+        let tree = FakeTree::new("hwmon");
+
+        tree.write("sys/class/hwmon/hwmon0/name", "k10temp\n");
+        tree.write("sys/class/hwmon/hwmon0/temp1_input", "45000\n");
+        tree.write("sys/class/hwmon/hwmon0/temp1_label", "Tctl\n");
+        tree.write("sys/class/hwmon/hwmon1/temp2_input", "61500\n");
+        tree.write("sys/class/hwmon/hwmon1/temp3_input", "unavailable\n");
+        tree.write("sys/class/hwmon/hwmon1/fan1_input", "1200\n");
+
+        assert_eq!(temperatures(&tree.paths()).unwrap(), vec![45.0, 61.5]);
+    }
+}
@@ -0,0 +1,89 @@
+use devices::{config_path, Color, Frame, Geometry, Keymap, Zone};
+use errors::{ErrorKind, Result, ResultExt};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use toml;
+
+#[derive(Debug, Deserialize)]
+struct ThemeFile {
+    name: Option<String>,
+    background: Option<String>,
+    #[serde(default)]
+    palette: BTreeMap<String, String>,
+    #[serde(default)]
+    assign: Vec<AssignmentFile>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AssignmentFile {
+    zone: String,
+    color: String,
+}
+
+// Colors for zones and key groups, applied in order on top of the background.
+#[derive(Debug, Clone)]
+pub struct Theme {
+    pub name: String,
+    pub background: Color,
+    pub assignments: Vec<(Zone, Color)>,
+}
+
+impl Theme {
+    // A theme file, or the name of one in the themes directory of the user's
+    // config (e.g. "nord" for ~/.config/razer_test_test/themes/nord.toml).
+    pub fn find(name: &str) -> Result<Theme> {
+        let path = Path::new(name);
+
+        if path.exists() {
+            return Theme::load(path);
+        }
+        match config_path("RAZER_TEST_THEMES", "themes").map(|themes| themes.join(format!("{}.toml", name))) {
+            Some(ref path) if path.exists() => Theme::load(path),
+            _ => Err(ErrorKind::InvalidTheme(format!("'{}' not found", name)).into()),
+        }
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Theme> {
+        let path = path.as_ref();
+        let default_name = path.file_stem().map(|stem| stem.to_string_lossy().into_owned());
+
+        Theme::parse(&fs::read_to_string(path)?, default_name)
+            .chain_err(|| ErrorKind::InvalidTheme(format!("in '{}'", path.display())))
+    }
+
+    pub fn parse(content: &str, default_name: Option<String>) -> Result<Theme> {
+        let file: ThemeFile = toml::from_str(content)?;
+        // Palette entries are referred to by name, anything else is a color.
+        let color = |value: &str| match file.palette.get(value) {
+            Some(color) => Color::parse(color),
+            None => Color::parse(value),
+        };
+        let mut assignments = Vec::new();
+
+        for assignment in &file.assign {
+            assignments.push((assignment.zone.parse()?, color(&assignment.color)?));
+        }
+
+        Ok(Theme {
+            name: file.name.clone().or(default_name).unwrap_or_default(),
+            background: match file.background {
+                Some(ref background) => color(background)?,
+                None => Color::default(),
+            },
+            assignments,
+        })
+    }
+
+    pub fn render(&self, geometry: Geometry, keymap: Option<&Keymap>) -> Frame {
+        let mut frame = Frame::new(geometry);
+
+        frame.fill(self.background);
+        for &(ref zone, color) in &self.assignments {
+            for (row, col) in zone.cells(geometry, keymap) {
+                frame.set(row, col, color);
+            }
+        }
+        frame
+    }
+}
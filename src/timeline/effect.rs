@@ -0,0 +1,44 @@
+use super::keyframes::Timeline;
+use animation::Effect;
+use devices::{Frame, Keymap};
+
+pub struct TimelineEffect {
+    timeline: Timeline,
+    keymap: Option<&'static Keymap>,
+    cells: Vec<Vec<(usize, usize)>>,
+}
+
+impl TimelineEffect {
+    pub fn new(timeline: Timeline, keymap: Option<&'static Keymap>) -> TimelineEffect {
+        TimelineEffect {
+            timeline,
+            keymap,
+            cells: Vec::new(),
+        }
+    }
+}
+
+impl Effect for TimelineEffect {
+    fn render(&mut self, t: f64, frame: &mut Frame) {
+        if self.cells.is_empty() {
+            let keymap = self.keymap;
+
+            self.cells = self
+                .timeline
+                .tracks
+                .iter()
+                .map(|track| track.zone.cells(frame.geometry(), keymap))
+                .collect();
+        }
+        let position = self.timeline.position(t);
+
+        // Later tracks are painted over earlier ones.
+        for (track, cells) in self.timeline.tracks.iter().zip(self.cells.iter()) {
+            let color = track.sample(position);
+
+            for &(row, col) in cells {
+                frame.set(row, col, color);
+            }
+        }
+    }
+}
@@ -0,0 +1,205 @@
+use animation::Easing;
+use devices::{Color, Zone};
+use errors::{ErrorKind, Result};
+use serde_json;
+use std::fs;
+use std::path::Path;
+use toml;
+
+#[derive(Debug, Deserialize)]
+struct TimelineFile {
+    loop_start: Option<f64>,
+    loop_end: Option<f64>,
+    #[serde(rename = "track", alias = "tracks", default)]
+    tracks: Vec<TrackFile>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TrackFile {
+    device: Option<String>,
+    zone: Option<String>,
+    #[serde(rename = "keyframe", alias = "keyframes", default)]
+    keyframes: Vec<KeyframeFile>,
+}
+
+#[derive(Debug, Deserialize)]
+struct KeyframeFile {
+    time: f64,
+    color: String,
+    easing: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Keyframe {
+    pub time: f64,
+    pub color: Color,
+    // Easing of the transition from the previous keyframe to this one.
+    pub easing: Easing,
+}
+
+#[derive(Debug, Clone)]
+pub struct Track {
+    pub device: Option<String>,
+    pub zone: Zone,
+    pub keyframes: Vec<Keyframe>,
+}
+
+impl Track {
+    pub fn matches(&self, device_name: &str) -> bool {
+        match self.device {
+            Some(ref device) => device_name.to_lowercase().contains(&device.to_lowercase()),
+            None => true,
+        }
+    }
+
+    // True if the track only switches between static colors, i.e. could be
+    // played by sending a static effect at each keyframe.
+    pub fn is_static(&self) -> bool {
+        self.keyframes.iter().skip(1).all(|keyframe| keyframe.easing == Easing::Step)
+    }
+
+    pub fn sample(&self, t: f64) -> Color {
+        let next = self.keyframes.iter().position(|keyframe| keyframe.time > t);
+
+        match next {
+            Some(0) => self.keyframes[0].color,
+            Some(index) => {
+                let from = &self.keyframes[index - 1];
+                let to = &self.keyframes[index];
+                let progress = (t - from.time) / (to.time - from.time);
+
+                from.color.mix(to.color, to.easing.apply(progress))
+            }
+            None => self.keyframes[self.keyframes.len() - 1].color,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Timeline {
+    pub tracks: Vec<Track>,
+    pub loop_range: Option<(f64, f64)>,
+}
+
+impl Timeline {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Timeline> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)?;
+
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("json") => Timeline::parse_json(&content),
+            _ => Timeline::parse_toml(&content),
+        }
+    }
+
+    pub fn parse_toml(content: &str) -> Result<Timeline> {
+        Timeline::from_file(toml::from_str(content)?)
+    }
+
+    pub fn parse_json(content: &str) -> Result<Timeline> {
+        Timeline::from_file(serde_json::from_str(content)?)
+    }
+
+    fn from_file(file: TimelineFile) -> Result<Timeline> {
+        let mut tracks = Vec::new();
+
+        for (index, track) in file.tracks.into_iter().enumerate() {
+            if track.keyframes.is_empty() {
+                return Err(invalid(format!("track {} has no keyframes", index)));
+            }
+            let mut keyframes = Vec::new();
+
+            for keyframe in track.keyframes {
+                if !keyframe.time.is_finite() || keyframe.time < 0.0 {
+                    return Err(invalid(format!("track {}: negative or infinite keyframe time", index)));
+                }
+                keyframes.push(Keyframe {
+                    time: keyframe.time,
+                    color: Color::parse(&keyframe.color)?,
+                    easing: match keyframe.easing {
+                        Some(easing) => easing.parse()?,
+                        None => Easing::default(),
+                    },
+                });
+            }
+            keyframes.sort_by(|a, b| a.time.partial_cmp(&b.time).unwrap());
+
+            tracks.push(Track {
+                device: track.device,
+                zone: match track.zone {
+                    Some(zone) => zone.parse()?,
+                    None => Zone::All,
+                },
+                keyframes,
+            });
+        }
+
+        let mut timeline = Timeline {
+            tracks,
+            loop_range: None,
+        };
+        if file.loop_start.is_some() || file.loop_end.is_some() {
+            let start = file.loop_start.unwrap_or(0.0);
+            let end = file.loop_end.unwrap_or_else(|| timeline.duration());
+
+            if !start.is_finite() || !end.is_finite() || start < 0.0 || end <= start {
+                return Err(invalid(format!("invalid loop from {} to {}", start, end)));
+            }
+            timeline.loop_range = Some((start, end));
+        }
+
+        Ok(timeline)
+    }
+
+    pub fn for_device(&self, device_name: &str) -> Timeline {
+        Timeline {
+            tracks: self
+                .tracks
+                .iter()
+                .filter(|track| track.matches(device_name))
+                .cloned()
+                .collect(),
+            loop_range: self.loop_range,
+        }
+    }
+
+    pub fn duration(&self) -> f64 {
+        self.tracks
+            .iter()
+            .filter_map(|track| track.keyframes.last())
+            .map(|keyframe| keyframe.time)
+            .fold(0.0, f64::max)
+    }
+
+    // Maps the playback time to the position in the timeline, wrapping around
+    // inside of the loop range once its end has been reached.
+    pub fn position(&self, t: f64) -> f64 {
+        match self.loop_range {
+            Some((start, end)) if t >= end => start + (t - start) % (end - start),
+            _ => t,
+        }
+    }
+}
+
+fn invalid(message: String) -> ::errors::Error {
+    ErrorKind::InvalidTimeline(message).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn track(time: &str) -> String {
+        format!(
+            "[[track]]\n[[track.keyframe]]\ntime = 0.0\ncolor = \"red\"\n[[track.keyframe]]\ntime = {}\ncolor = \"blue\"\n",
+            time
+        )
+    }
+
+    #[test]
+    fn rejects_infinite_times() {
+        assert!(Timeline::parse_toml(&track("2.0")).is_ok());
+        assert!(Timeline::parse_toml(&track("inf")).is_err());
+        assert!(Timeline::parse_toml(&format!("loop_end = inf\n{}", track("2.0"))).is_err());
+    }
+}
@@ -0,0 +1,5 @@
+mod effect;
+mod keyframes;
+
+pub use self::effect::*;
+pub use self::keyframes::*;
@@ -0,0 +1,229 @@
+use animation::Effect;
+use devices::{Color, Frame, Keymap};
+use errors::{ErrorKind, Result};
+use std::str::FromStr;
+
+// Parses "90", "90s", "25m" or "1h30m" into seconds.
+pub fn parse_duration(duration_str: &str) -> Result<f64> {
+    let invalid = || ErrorKind::InvalidParameter("duration".to_string(), duration_str.to_string());
+    let mut seconds = 0.0;
+    let mut number = String::new();
+
+    for c in duration_str.trim().chars() {
+        let unit = match c {
+            'h' => 3600.0,
+            'm' => 60.0,
+            's' => 1.0,
+            _ => {
+                number.push(c);
+                continue;
+            }
+        };
+        seconds += number.parse::<f64>().map_err(|_| invalid())? * unit;
+        number.clear();
+    }
+    if !number.is_empty() {
+        seconds += number.parse::<f64>().map_err(|_| invalid())?;
+    }
+
+    if !seconds.is_finite() || seconds <= 0.0 {
+        return Err(invalid().into());
+    }
+    Ok(seconds)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BarRow {
+    Function,
+    Number,
+}
+
+impl BarRow {
+    fn key_names(self) -> &'static [&'static str] {
+        match self {
+            BarRow::Function => &[
+                "f1", "f2", "f3", "f4", "f5", "f6", "f7", "f8", "f9", "f10", "f11", "f12",
+            ],
+            BarRow::Number => &["1", "2", "3", "4", "5", "6", "7", "8", "9", "0", "minus", "equal"],
+        }
+    }
+}
+
+impl FromStr for BarRow {
+    type Err = ::errors::Error;
+
+    fn from_str(s: &str) -> Result<BarRow> {
+        match s {
+            "function" => Ok(BarRow::Function),
+            "number" => Ok(BarRow::Number),
+            _ => Err(ErrorKind::InvalidParameter("row".to_string(), s.to_string()).into()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Phase {
+    pub length: f64,
+    pub color: Color,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Flash {
+    pub color: Color,
+    pub count: u32,
+    // Flashes per second
+    pub rate: f64,
+}
+
+impl Flash {
+    fn length(&self) -> f64 {
+        if self.rate > 0.0 {
+            self.count as f64 / self.rate
+        } else {
+            0.0
+        }
+    }
+}
+
+// Counts down each phase as a shrinking bar, followed by a flash of the whole device.
+// Without a keymap the bar spans the top row, a single LED just dims.
+pub struct Countdown {
+    phases: Vec<Phase>,
+    empty: Color,
+    flash: Flash,
+    cells: Vec<(usize, usize)>,
+}
+
+impl Countdown {
+    pub fn new(phases: Vec<Phase>, empty: Color, flash: Flash, row: BarRow, keymap: Option<&Keymap>) -> Countdown {
+        let cells = keymap
+            .map(|keymap| {
+                row.key_names()
+                    .iter()
+                    .filter_map(|name| keymap.by_name(name))
+                    .map(|key| (key.row, key.col))
+                    .collect()
+            }).unwrap_or_default();
+
+        Countdown {
+            phases,
+            empty,
+            flash,
+            cells,
+        }
+    }
+
+    pub fn length(&self) -> f64 {
+        self.phases
+            .iter()
+            .map(|phase| phase.length + self.flash.length())
+            .sum()
+    }
+
+    fn render_bar(&self, phase: &Phase, elapsed: f64, frame: &mut Frame) {
+        let cells = if self.cells.is_empty() {
+            (0..frame.cols()).map(|col| (0, col)).collect()
+        } else {
+            self.cells.clone()
+        };
+        let lit = (1.0 - elapsed / phase.length) * cells.len() as f64;
+
+        for (i, &(row, col)) in cells.iter().enumerate() {
+            if row < frame.rows() && col < frame.cols() {
+                frame.set(row, col, self.empty.mix(phase.color, lit - i as f64));
+            }
+        }
+    }
+}
+
+impl Effect for Countdown {
+    fn render(&mut self, t: f64, frame: &mut Frame) {
+        let mut start = 0.0;
+
+        frame.fill(Color::default());
+
+        for phase in self.phases.iter() {
+            let elapsed = t - start;
+
+            if elapsed < phase.length {
+                self.render_bar(phase, elapsed.max(0.0), frame);
+                return;
+            }
+            let flashing = elapsed - phase.length;
+            if flashing < self.flash.length() {
+                if (flashing * self.flash.rate).fract() < 0.5 {
+                    frame.fill(self.flash.color);
+                }
+                return;
+            }
+            start += phase.length + self.flash.length();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use devices::Geometry;
+
+    const RED: Color = Color { red: 255, green: 0, blue: 0 };
+    const GREEN: Color = Color { red: 0, green: 255, blue: 0 };
+    const WHITE: Color = Color { red: 255, green: 255, blue: 255 };
+
+    fn countdown() -> Countdown {
+        let phases = vec![Phase { length: 4.0, color: RED }, Phase { length: 2.0, color: GREEN }];
+        let flash = Flash {
+            color: WHITE,
+            count: 2,
+            rate: 2.0,
+        };
+
+        // Without a keymap the bar is the top row.
+        Countdown::new(phases, Color::default(), flash, BarRow::Function, None)
+    }
+
+    fn render(countdown: &mut Countdown, t: f64) -> Frame {
+        let mut frame = Frame::new(Geometry::new(2, 4));
+        countdown.render(t, &mut frame);
+        frame
+    }
+
+    fn bar(frame: &Frame) -> Vec<Color> {
+        frame.row(0).to_vec()
+    }
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(parse_duration("90").unwrap(), 90.0);
+        assert_eq!(parse_duration("25m").unwrap(), 1500.0);
+        assert_eq!(parse_duration("1h30m").unwrap(), 5400.0);
+        for invalid in &["", "0", "-5m", "5x", "inf", "1e400", "nan"] {
+            assert!(parse_duration(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn shrinks_the_bar() {
+        let mut countdown = countdown();
+        let black = Color::default();
+
+        assert_eq!(bar(&render(&mut countdown, 0.0)), vec![RED; 4]);
+        assert_eq!(bar(&render(&mut countdown, 1.0)), vec![RED, RED, RED, black]);
+        assert_eq!(bar(&render(&mut countdown, 2.5)), vec![RED, black.mix(RED, 0.5), black, black]);
+        assert_eq!(render(&mut countdown, 2.5).row(1), &[black; 4][..]);
+    }
+
+    #[test]
+    fn flashes_between_phases() {
+        let mut countdown = countdown();
+
+        assert_eq!(countdown.length(), 8.0);
+        assert_eq!(bar(&render(&mut countdown, 4.1)), vec![WHITE; 4]);
+        assert_eq!(render(&mut countdown, 4.1).row(1), &[WHITE; 4][..]);
+        assert_eq!(bar(&render(&mut countdown, 4.3)), vec![Color::default(); 4]);
+        assert_eq!(bar(&render(&mut countdown, 4.6)), vec![WHITE; 4]);
+        // The second phase starts after the flash.
+        assert_eq!(bar(&render(&mut countdown, 5.0)), vec![GREEN; 4]);
+        assert_eq!(bar(&render(&mut countdown, 8.5)), vec![Color::default(); 4]);
+    }
+}
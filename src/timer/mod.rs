@@ -0,0 +1,5 @@
+mod countdown;
+mod wall_clock;
+
+pub use self::countdown::*;
+pub use self::wall_clock::*;
@@ -0,0 +1,114 @@
+use animation::Effect;
+use devices::{Color, Frame, Keymap};
+use libc;
+use std::mem;
+use std::ptr;
+
+static HOUR_KEYS: &[&str] = &["f12", "f1", "f2", "f3", "f4", "f5", "f6", "f7", "f8", "f9", "f10", "f11"];
+static DIGIT_KEYS: &[&str] = &["kp0", "kp1", "kp2", "kp3", "kp4", "kp5", "kp6", "kp7", "kp8", "kp9"];
+
+// Seconds each digit is shown, then off for the rest of the slot.
+const DIGIT_ON: f64 = 0.6;
+const DIGIT_SLOT: f64 = 0.8;
+const PAUSE: f64 = 1.6;
+
+fn local_time() -> (u32, u32) {
+    unsafe {
+        let now = libc::time(ptr::null_mut());
+        let mut tm: libc::tm = mem::zeroed();
+
+        libc::localtime_r(&now, &mut tm);
+        (tm.tm_hour as u32, tm.tm_min as u32)
+    }
+}
+
+// Shows the hour on the F-row and spells out HH MM one digit after another on the numpad.
+pub struct WallClock {
+    keymap: &'static Keymap,
+    hour: Color,
+    minute: Color,
+    background: Color,
+}
+
+impl WallClock {
+    pub fn new(keymap: &'static Keymap, hour: Color, minute: Color, background: Color) -> WallClock {
+        WallClock {
+            keymap,
+            hour,
+            minute,
+            background,
+        }
+    }
+
+    fn set_key(&self, name: &str, color: Color, frame: &mut Frame) {
+        if let Some(key) = self.keymap.by_name(name) {
+            if key.row < frame.rows() && key.col < frame.cols() {
+                frame.set(key.row, key.col, color);
+            }
+        }
+    }
+
+    fn render_time(&self, hour: u32, minute: u32, t: f64, frame: &mut Frame) {
+        frame.fill(self.background);
+        self.set_key(HOUR_KEYS[hour as usize % 12], self.hour, frame);
+
+        let position = t % (4.0 * DIGIT_SLOT + PAUSE);
+        let slot = (position / DIGIT_SLOT) as usize;
+
+        if slot < 4 && position - slot as f64 * DIGIT_SLOT < DIGIT_ON {
+            let (digit, color) = match slot {
+                0 => (hour / 10, self.hour),
+                1 => (hour % 10, self.hour),
+                2 => (minute / 10, self.minute),
+                _ => (minute % 10, self.minute),
+            };
+            self.set_key(DIGIT_KEYS[digit as usize], color, frame);
+        }
+    }
+}
+
+impl Effect for WallClock {
+    fn render(&mut self, t: f64, frame: &mut Frame) {
+        let (hour, minute) = local_time();
+        self.render_time(hour, minute, t, frame);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use devices::find_layouts;
+
+    const HOUR: Color = Color { red: 255, green: 128, blue: 0 };
+    const MINUTE: Color = Color { red: 0, green: 128, blue: 255 };
+
+    // Keys lit at 14:35 after `t` seconds.
+    fn lit(t: f64) -> Vec<(&'static str, Color)> {
+        let layout = find_layouts("blackwidow").unwrap().remove(0);
+        let keymap = layout.keymap.unwrap();
+        let clock = WallClock::new(keymap, HOUR, MINUTE, Color::default());
+        let mut frame = Frame::new(layout.geometry);
+
+        clock.render_time(14, 35, t, &mut frame);
+        let mut keys: Vec<(&'static str, Color)> = keymap
+            .keys()
+            .iter()
+            .filter(|key| frame.get(key.row, key.col) != Color::default())
+            .map(|key| (key.name, frame.get(key.row, key.col)))
+            .collect();
+        keys.sort_by_key(|&(name, _)| name);
+        keys
+    }
+
+    #[test]
+    fn spells_out_the_time() {
+        assert_eq!(lit(0.1), vec![("f2", HOUR), ("kp1", HOUR)]);
+        assert_eq!(lit(0.7), vec![("f2", HOUR)]);
+        assert_eq!(lit(0.9), vec![("f2", HOUR), ("kp4", HOUR)]);
+        assert_eq!(lit(1.7), vec![("f2", HOUR), ("kp3", MINUTE)]);
+        assert_eq!(lit(2.5), vec![("f2", HOUR), ("kp5", MINUTE)]);
+        // Pause, then again from the first digit.
+        assert_eq!(lit(3.5), vec![("f2", HOUR)]);
+        assert_eq!(lit(4.9), vec![("f2", HOUR), ("kp1", HOUR)]);
+    }
+}
@@ -0,0 +1,56 @@
+use super::cancel::Cancel;
+use super::fade::BrightnessFade;
+use animation::Clock;
+use devices::{Device, LedZone, Persistence};
+use errors::{ErrorKind, Result};
+use std::time::Duration;
+
+// Steps the brightness of every device (or only its `zone` LED) along its
+// fade, `rate` times a second.
+// Devices are only written to when their value changes. Returns false if the
+// fades were cancelled before they ended, leaving devices where they were.
+pub fn run_brightness_fades<C: Clock>(
+    clock: &C,
+    fades: &[(Box<Device>, BrightnessFade)],
+    zone: Option<LedZone>,
+    rate: u32,
+    cancel: &Cancel,
+) -> Result<bool> {
+    if rate == 0 {
+        return Err(ErrorKind::InvalidFrameRate(rate).into());
+    }
+    let start = clock.now();
+    let interval = Duration::from_secs(1) / rate;
+    let duration = fades
+        .iter()
+        .map(|&(_, fade)| fade.transition.duration)
+        .max()
+        .unwrap_or_default();
+    let mut last = vec![None; fades.len()];
+
+    loop {
+        if cancel.is_cancelled() {
+            return Ok(false);
+        }
+        let elapsed = clock.now() - start;
+
+        for (&(ref device, fade), last) in fades.iter().zip(last.iter_mut()) {
+            let value = fade.value(elapsed.as_secs_f64());
+
+            if *last != Some(value) {
+                let result = match zone {
+                    Some(zone) => device.set_zone_brightness(zone, value, Persistence::Transient),
+                    None => device.set_brightness(value, Persistence::Transient),
+                };
+                if let Err(error) = result {
+                    warn!("{}: setting brightness failed: {}", device.name(), error);
+                }
+                *last = Some(value);
+            }
+        }
+        if elapsed >= duration {
+            return Ok(true);
+        }
+        clock.sleep(interval.min(duration - elapsed));
+    }
+}
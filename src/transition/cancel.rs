@@ -0,0 +1,48 @@
+use libc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Once};
+
+lazy_static! {
+    static ref INTERRUPT: Cancel = Cancel::new();
+}
+
+static INSTALL: Once = Once::new();
+
+extern "C" fn interrupted(_: libc::c_int) {
+    INTERRUPT.cancel();
+    // A second Ctrl-C quits right away.
+    unsafe {
+        libc::signal(libc::SIGINT, libc::SIG_DFL);
+    }
+}
+
+// Stops a running transition where it is. Clones share the flag, so a handle
+// can be kept to cancel a transition that has been handed elsewhere.
+#[derive(Debug, Clone, Default)]
+pub struct Cancel {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl Cancel {
+    pub fn new() -> Cancel {
+        Default::default()
+    }
+
+    // A handle that is cancelled by Ctrl-C.
+    pub fn on_interrupt() -> Cancel {
+        let cancel = INTERRUPT.clone();
+
+        INSTALL.call_once(|| unsafe {
+            libc::signal(libc::SIGINT, interrupted as extern "C" fn(libc::c_int) as libc::sighandler_t);
+        });
+        cancel
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+}
@@ -0,0 +1,66 @@
+use animation::{Easing, Effect};
+use devices::{Brightness, Color, Frame};
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transition {
+    pub duration: Duration,
+    pub easing: Easing,
+}
+
+impl Transition {
+    pub fn new(duration: Duration, easing: Easing) -> Transition {
+        Transition { duration, easing }
+    }
+
+    // Eased progress `t` seconds into the transition, 1 once it is over.
+    pub fn progress(&self, t: f64) -> f64 {
+        let duration = self.duration.as_secs_f64();
+
+        if duration <= 0.0 {
+            1.0
+        } else {
+            self.easing.apply(t / duration)
+        }
+    }
+}
+
+// Fills the whole frame with a color fading from `from` to `to`.
+pub struct ColorFade {
+    from: Color,
+    to: Color,
+    transition: Transition,
+}
+
+impl ColorFade {
+    pub fn new(from: Color, to: Color, transition: Transition) -> ColorFade {
+        ColorFade { from, to, transition }
+    }
+}
+
+impl Effect for ColorFade {
+    fn render(&mut self, t: f64, frame: &mut Frame) {
+        frame.fill(self.from.mix(self.to, self.transition.progress(t)));
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BrightnessFade {
+    pub from: Brightness,
+    pub to: Brightness,
+    pub transition: Transition,
+}
+
+impl BrightnessFade {
+    pub fn new(from: Brightness, to: Brightness, transition: Transition) -> BrightnessFade {
+        BrightnessFade { from, to, transition }
+    }
+
+    // Whole percents, finer steps are not worth a report.
+    pub fn value(&self, t: f64) -> Brightness {
+        let (from, to) = (self.from.percent(), self.to.percent());
+        let percent = (from + (to - from) * self.transition.progress(t)).round();
+
+        Brightness::from_percent(percent).unwrap_or(self.to)
+    }
+}
@@ -0,0 +1,7 @@
+mod brightness;
+mod cancel;
+mod fade;
+
+pub use self::brightness::*;
+pub use self::cancel::*;
+pub use self::fade::*;